@@ -42,16 +42,29 @@ impl fmt::Display for UserBuilderError {
 impl Error for UserBuilderError {}
 
 #[derive(Default)]
-struct UserBuilder {
+struct UserBuilder<C: Clock = SystemClock> {
     username: Option<Username>,
     email_address: Option<EmailAddress>,
     date_of_birth: Option<DateOfBirth>,
+    clock: C,
 }
 
 impl UserBuilder {
     fn new() -> Self {
         Self::default()
     }
+}
+
+impl<C: Clock> UserBuilder<C> {
+    /// Checks ages against `clock` instead of the real date.
+    fn with_clock<D: Clock>(self, clock: D) -> UserBuilder<D> {
+        UserBuilder {
+            username: self.username,
+            email_address: self.email_address,
+            date_of_birth: self.date_of_birth,
+            clock,
+        }
+    }
 
     fn with_username(mut self, username: Username) -> Self {
         self.username = Some(username);
@@ -64,7 +77,7 @@ impl UserBuilder {
     }
 
     fn with_date_of_birth(mut self, date_of_birth: DateOfBirth) -> Result<Self, UserBuilderError> {
-        if date_of_birth.get_age() < 21 {
+        if date_of_birth.age(&self.clock) < 21 {
             return Err(UserBuilderError::NotOldEnough);
         }
         self.date_of_birth = Some(date_of_birth);
@@ -99,5 +112,47 @@ fn main() -> anyhow::Result<()> {
         .build();
     assert!(user_result.is_err());
 
+    // Ages are checked against a clock, so we can check them on any day
+    let turns_21 = FixedClock::new(Date::from_str("2030-05-01")?);
+    let user_result = UserBuilder::new()
+        .with_clock(turns_21)
+        .with_username(Username::from_str("Fio")?)
+        .with_email(EmailAddress::from_str("fio@example.com")?)
+        .with_date_of_birth(DateOfBirth::from_str("2009-05-01")?)?
+        .build();
+    assert!(user_result.is_ok());
+
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_on(today: &str, date_of_birth: &str) -> Result<User, UserBuilderError> {
+        UserBuilder::new()
+            .with_clock(FixedClock::new(Date::from_str(today).unwrap()))
+            .with_username(Username::from_str("Yuki").unwrap())
+            .with_email(EmailAddress::from_str("yuki@example.com").unwrap())
+            .with_date_of_birth(DateOfBirth::from_str(date_of_birth).unwrap())?
+            .build()
+    }
+
+    #[test]
+    fn test_old_enough_from_21st_birthday() {
+        assert!(matches!(
+            build_on("2030-04-30", "2009-05-01"),
+            Err(UserBuilderError::NotOldEnough)
+        ));
+        assert!(build_on("2030-05-01", "2009-05-01").is_ok());
+    }
+
+    #[test]
+    fn test_leap_day_birthday_waits_for_march() {
+        assert!(matches!(
+            build_on("2025-02-28", "2004-02-29"),
+            Err(UserBuilderError::NotOldEnough)
+        ));
+        assert!(build_on("2025-03-01", "2004-02-29").is_ok());
+    }
+}
@@ -22,12 +22,13 @@ struct User {
 }
 
 impl User {
-    fn new(
+    fn new<C: Clock>(
         username: Username,
         email_address: EmailAddress,
         date_of_birth: DateOfBirth,
+        clock: &C,
     ) -> Result<Self, UserNotOldEnough> {
-        if date_of_birth.get_age() < 21 {
+        if date_of_birth.age(clock) < 21 {
             return Err(UserNotOldEnough);
         }
 
@@ -60,6 +61,7 @@ fn main() -> anyhow::Result<()> {
         Username::from_str("Yuki")?,
         EmailAddress::from_str("yuki@example.com")?,
         DateOfBirth::from_str("2009-05-01")?,
+        &SystemClock,
     )?;
 
     dbg!(user_instantiate);
@@ -0,0 +1,57 @@
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ResolveError {
+    /// Nothing was registered to build the named type.
+    MissingProvider(&'static str),
+    /// Each type needed the next to be built, and the last is the first again.
+    Cycle(Vec<&'static str>),
+    /// The provider for the named type ran but returned an error.
+    Failed {
+        type_name: &'static str,
+        source: anyhow::Error,
+    },
+}
+
+impl fmt::Display for ResolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingProvider(type_name) => write!(f, "No provider for {type_name}"),
+            Self::Cycle(path) => write!(f, "Dependency cycle: {}", path.join(" -> ")),
+            Self::Failed { type_name, source } => {
+                write!(f, "Could not provide {type_name}: {source}")
+            }
+        }
+    }
+}
+
+impl Error for ResolveError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Failed { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl ResolveError {
+    /// Wraps an error from `type_name`'s provider. Resolution errors from
+    /// further down the graph are passed through so the root cause isn't
+    /// buried, except that a cycle picks up each type on the way back out
+    /// until it's complete.
+    pub(crate) fn failed(type_name: &'static str, error: anyhow::Error) -> Self {
+        match error.downcast::<ResolveError>() {
+            Ok(Self::Cycle(mut path)) => {
+                let complete = path.len() > 1 && path.first() == path.last();
+                if !complete {
+                    path.insert(0, type_name);
+                }
+                Self::Cycle(path)
+            }
+            Ok(error) => error,
+            Err(source) => Self::Failed { type_name, source },
+        }
+    }
+}
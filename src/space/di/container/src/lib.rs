@@ -0,0 +1,15 @@
+//! Wires an application's dependencies together so `main` doesn't have to.
+//!
+//! Prefer [`Provide`]: implement it on a module struct for each type the
+//! application needs, and asking for something the module can't build is a
+//! compile error. When the set of types isn't known up front, the
+//! [`Container`] resolves them by [`TypeId`](std::any::TypeId) at runtime
+//! instead, reporting missing providers and cycles as a [`ResolveError`].
+
+pub mod error;
+pub mod provide;
+pub mod runtime;
+
+pub use crate::error::ResolveError;
+pub use crate::provide::{Provide, Singleton};
+pub use crate::runtime::{Container, Scope};
@@ -0,0 +1,293 @@
+use std::any::type_name;
+use std::mem;
+use std::sync::{Arc, Condvar, Mutex, PoisonError};
+use std::thread::{self, ThreadId};
+
+use crate::error::ResolveError;
+
+/// Builds a `T`.
+///
+/// Implement this on your application's module once for each type it can
+/// provide, asking the module for each dependency in turn. The compiler then
+/// checks the whole graph: asking for a type with no provider doesn't build.
+///
+/// ```
+/// use container::Provide;
+///
+/// struct Config(String);
+/// struct Greeter(String);
+///
+/// struct Module;
+///
+/// impl Provide<Config> for Module {
+///     fn provide(&self) -> anyhow::Result<Config> {
+///         Ok(Config("Hello".to_string()))
+///     }
+/// }
+///
+/// impl Provide<Greeter> for Module {
+///     fn provide(&self) -> anyhow::Result<Greeter> {
+///         let config: Config = self.provide()?;
+///         Ok(Greeter(config.0))
+///     }
+/// }
+///
+/// let greeter: Greeter = Module.provide().unwrap();
+/// assert_eq!(greeter.0, "Hello");
+/// ```
+pub trait Provide<T> {
+    fn provide(&self) -> anyhow::Result<T>;
+}
+
+enum State<T> {
+    Empty,
+    Building(ThreadId),
+    Ready(Arc<T>),
+}
+
+/// Holds the one instance of a type a module shares with everything that
+/// asks for it, building it the first time it's needed.
+pub struct Singleton<T> {
+    state: Mutex<State<T>>,
+    built: Condvar,
+}
+
+impl<T> Default for Singleton<T> {
+    fn default() -> Self {
+        Self {
+            state: Mutex::new(State::Empty),
+            built: Condvar::new(),
+        }
+    }
+}
+
+impl<T> Singleton<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the instance, building it with `build` if this is the first
+    /// time it's been asked for. If another thread is already building it we
+    /// wait for that instead of building a second one.
+    ///
+    /// `build` needing the instance it's building is a cycle, which is
+    /// reported as [`ResolveError::Cycle`] rather than deadlocking. Only
+    /// cycles within one thread are caught: if `build` waits on another
+    /// thread that is itself waiting for this instance, both wait forever.
+    ///
+    /// If `build` panics the instance is left unbuilt, so threads waiting on
+    /// it stop waiting and the next call tries again.
+    pub fn get_or_try_init<F>(&self, build: F) -> anyhow::Result<Arc<T>>
+    where
+        F: FnOnce() -> anyhow::Result<T>,
+    {
+        let me = thread::current().id();
+        let mut state = self.state.lock().expect("singleton poisoned");
+        loop {
+            match &*state {
+                State::Ready(instance) => return Ok(instance.clone()),
+                State::Building(builder) if *builder == me => {
+                    return Err(ResolveError::Cycle(vec![type_name::<T>()]).into());
+                }
+                State::Building(_) => state = self.built.wait(state).expect("singleton poisoned"),
+                State::Empty => break,
+            }
+        }
+        *state = State::Building(me);
+        // Don't hold the lock while building, the instance may need others
+        drop(state);
+
+        let reset = ResetOnPanic(self);
+        let built = build();
+        mem::forget(reset);
+        let mut state = self.state.lock().expect("singleton poisoned");
+        let result = match built {
+            Ok(instance) => {
+                let instance = Arc::new(instance);
+                *state = State::Ready(instance.clone());
+                Ok(instance)
+            }
+            Err(error) => {
+                *state = State::Empty;
+                Err(ResolveError::failed(type_name::<T>(), error).into())
+            }
+        };
+        self.built.notify_all();
+        result
+    }
+}
+
+/// Puts a [`Singleton`] back to empty if the build it guards unwinds, which
+/// otherwise would leave it building forever.
+struct ResetOnPanic<'a, T>(&'a Singleton<T>);
+
+impl<T> Drop for ResetOnPanic<'_, T> {
+    fn drop(&mut self) {
+        let mut state = self.0.state.lock().unwrap_or_else(PoisonError::into_inner);
+        *state = State::Empty;
+        self.0.built.notify_all();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[derive(Debug)]
+    struct Config {
+        greeting: String,
+    }
+
+    struct Greeter {
+        config: Arc<Config>,
+    }
+
+    #[derive(Debug)]
+    struct Chicken;
+    #[derive(Debug)]
+    struct Egg;
+
+    #[derive(Default)]
+    struct Module {
+        config: Singleton<Config>,
+        configs_built: AtomicUsize,
+    }
+
+    impl Provide<Arc<Config>> for Module {
+        fn provide(&self) -> anyhow::Result<Arc<Config>> {
+            self.config.get_or_try_init(|| {
+                self.configs_built.fetch_add(1, Ordering::SeqCst);
+                Ok(Config {
+                    greeting: "Hello".to_string(),
+                })
+            })
+        }
+    }
+
+    impl Provide<Greeter> for Module {
+        fn provide(&self) -> anyhow::Result<Greeter> {
+            Ok(Greeter {
+                config: self.provide()?,
+            })
+        }
+    }
+
+    #[test]
+    fn test_singletons_are_shared() {
+        let module = Module::default();
+
+        let first: Greeter = module.provide().unwrap();
+        let second: Greeter = module.provide().unwrap();
+
+        assert_eq!(first.config.greeting, "Hello");
+        assert!(Arc::ptr_eq(&first.config, &second.config));
+        assert_eq!(module.configs_built.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_singletons_are_shared_between_threads() {
+        let module = Arc::new(Module::default());
+
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let module = module.clone();
+                thread::spawn(move || Provide::<Arc<Config>>::provide(&*module).unwrap())
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(module.configs_built.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_failed_singletons_are_retried() {
+        let singleton = Singleton::<Config>::new();
+
+        let error = singleton
+            .get_or_try_init(|| anyhow::bail!("no config file"))
+            .unwrap_err();
+        assert!(matches!(
+            error.downcast_ref(),
+            Some(ResolveError::Failed { .. })
+        ));
+
+        let config = singleton
+            .get_or_try_init(|| {
+                Ok(Config {
+                    greeting: "Hi".to_string(),
+                })
+            })
+            .unwrap();
+        assert_eq!(config.greeting, "Hi");
+    }
+
+    #[test]
+    fn test_panicking_singletons_are_retried() {
+        let singleton = Arc::new(Singleton::<Config>::new());
+        let (building, started) = std::sync::mpsc::channel();
+
+        let panicking = {
+            let singleton = singleton.clone();
+            thread::spawn(move || {
+                singleton.get_or_try_init(|| {
+                    building.send(()).unwrap();
+                    thread::sleep(std::time::Duration::from_millis(20));
+                    panic!("config file is corrupt")
+                })
+            })
+        };
+        started.recv().unwrap();
+
+        // Waits for the panicking build, then builds it instead
+        let config = singleton
+            .get_or_try_init(|| {
+                Ok(Config {
+                    greeting: "Hi".to_string(),
+                })
+            })
+            .unwrap();
+        assert_eq!(config.greeting, "Hi");
+        assert!(panicking.join().is_err());
+    }
+
+    #[derive(Default)]
+    struct Farm {
+        chicken: Singleton<Chicken>,
+        egg: Singleton<Egg>,
+    }
+
+    impl Provide<Arc<Chicken>> for Farm {
+        fn provide(&self) -> anyhow::Result<Arc<Chicken>> {
+            self.chicken.get_or_try_init(|| {
+                let _egg: Arc<Egg> = self.provide()?;
+                Ok(Chicken)
+            })
+        }
+    }
+
+    impl Provide<Arc<Egg>> for Farm {
+        fn provide(&self) -> anyhow::Result<Arc<Egg>> {
+            self.egg.get_or_try_init(|| {
+                let _chicken: Arc<Chicken> = self.provide()?;
+                Ok(Egg)
+            })
+        }
+    }
+
+    #[test]
+    fn test_singleton_cycle() {
+        let farm = Farm::default();
+
+        let error = Provide::<Arc<Chicken>>::provide(&farm).unwrap_err();
+        assert!(matches!(
+            error.downcast_ref(),
+            Some(ResolveError::Cycle(path)) if path.len() == 3
+                && path[0].ends_with("Chicken")
+                && path[1].ends_with("Egg")
+                && path[2].ends_with("Chicken")
+        ));
+    }
+}
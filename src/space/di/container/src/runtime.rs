@@ -0,0 +1,316 @@
+use std::any::{Any, TypeId, type_name};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use crate::error::ResolveError;
+use crate::provide::Provide;
+
+type Instance = Arc<dyn Any + Send + Sync>;
+type Build = Arc<dyn Fn(&Scope<'_>) -> anyhow::Result<Instance> + Send + Sync>;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Lifetime {
+    /// One instance for the whole container.
+    Singleton,
+    /// One instance for each [`Scope`], eg each request.
+    Scoped,
+}
+
+#[derive(Clone)]
+struct Provider {
+    lifetime: Lifetime,
+    build: Build,
+}
+
+/// Providers registered by type and resolved at runtime.
+///
+/// This is the fallback for when [`Provide`] can't be used because the types
+/// aren't known until runtime, eg plugins registering their own services. The
+/// price is that a missing provider or a cycle is only found when something
+/// is resolved, as a [`ResolveError`].
+#[derive(Default)]
+pub struct Container {
+    providers: HashMap<TypeId, Provider>,
+    singletons: Mutex<HashMap<TypeId, Instance>>,
+}
+
+impl Container {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn with_provider<T, F>(mut self, lifetime: Lifetime, build: F) -> Self
+    where
+        T: Any + Send + Sync,
+        F: Fn(&Scope<'_>) -> anyhow::Result<T> + Send + Sync + 'static,
+    {
+        let build: Build = Arc::new(move |scope| Ok(Arc::new(build(scope)?) as Instance));
+        self.providers
+            .insert(TypeId::of::<T>(), Provider { lifetime, build });
+        self
+    }
+
+    /// Registers `build` to make the one `T` shared by everything resolved
+    /// from this container. It's built the first time it's asked for.
+    pub fn with_singleton<T, F>(self, build: F) -> Self
+    where
+        T: Any + Send + Sync,
+        F: Fn(&Scope<'_>) -> anyhow::Result<T> + Send + Sync + 'static,
+    {
+        self.with_provider(Lifetime::Singleton, build)
+    }
+
+    /// Registers `build` to make a `T` for each [`Scope`], shared by
+    /// everything resolved from that scope.
+    pub fn with_scoped<T, F>(self, build: F) -> Self
+    where
+        T: Any + Send + Sync,
+        F: Fn(&Scope<'_>) -> anyhow::Result<T> + Send + Sync + 'static,
+    {
+        self.with_provider(Lifetime::Scoped, build)
+    }
+
+    /// Registers a singleton that has already been built.
+    pub fn with_instance<T: Any + Send + Sync>(mut self, instance: T) -> Self {
+        let instance: Instance = Arc::new(instance);
+        let build: Build = Arc::new(move |_| Ok(instance.clone()));
+        self.providers.insert(
+            TypeId::of::<T>(),
+            Provider {
+                lifetime: Lifetime::Singleton,
+                build,
+            },
+        );
+        self
+    }
+
+    /// Starts a new scope, eg for a request. Scoped instances live as long as
+    /// it does.
+    pub fn scope(&self) -> Scope<'_> {
+        Scope {
+            container: self,
+            instances: RefCell::default(),
+            resolving: RefCell::default(),
+        }
+    }
+
+    /// Resolves a `T` in a scope of its own.
+    pub fn resolve<T: Any + Send + Sync>(&self) -> Result<Arc<T>, ResolveError> {
+        self.scope().resolve()
+    }
+}
+
+/// A unit of work, such as a request, with its own scoped instances.
+pub struct Scope<'c> {
+    container: &'c Container,
+    instances: RefCell<HashMap<TypeId, Instance>>,
+    // The types being built right now, to spot cycles
+    resolving: RefCell<Vec<(TypeId, &'static str)>>,
+}
+
+impl Scope<'_> {
+    pub fn resolve<T: Any + Send + Sync>(&self) -> Result<Arc<T>, ResolveError> {
+        let instance = self.resolve_instance(TypeId::of::<T>(), type_name::<T>())?;
+        Ok(instance
+            .downcast()
+            .expect("providers are registered under their own type"))
+    }
+
+    fn cached(&self, id: TypeId, lifetime: Lifetime) -> Option<Instance> {
+        match lifetime {
+            Lifetime::Singleton => self
+                .container
+                .singletons
+                .lock()
+                .expect("container poisoned")
+                .get(&id)
+                .cloned(),
+            Lifetime::Scoped => self.instances.borrow().get(&id).cloned(),
+        }
+    }
+
+    fn resolve_instance(&self, id: TypeId, name: &'static str) -> Result<Instance, ResolveError> {
+        let provider = self
+            .container
+            .providers
+            .get(&id)
+            .ok_or(ResolveError::MissingProvider(name))?;
+        if let Some(instance) = self.cached(id, provider.lifetime) {
+            return Ok(instance);
+        }
+
+        {
+            let mut resolving = self.resolving.borrow_mut();
+            if let Some(start) = resolving.iter().position(|(resolving, _)| *resolving == id) {
+                let mut path: Vec<_> = resolving[start..].iter().map(|(_, name)| *name).collect();
+                path.push(name);
+                return Err(ResolveError::Cycle(path));
+            }
+            resolving.push((id, name));
+        }
+        let built = (provider.build)(self);
+        self.resolving.borrow_mut().pop();
+
+        let instance = built.map_err(|error| ResolveError::failed(name, error))?;
+        Ok(match provider.lifetime {
+            // Another scope may have beaten us to it, in which case theirs wins
+            Lifetime::Singleton => self
+                .container
+                .singletons
+                .lock()
+                .expect("container poisoned")
+                .entry(id)
+                .or_insert(instance)
+                .clone(),
+            Lifetime::Scoped => {
+                self.instances.borrow_mut().insert(id, instance.clone());
+                instance
+            }
+        })
+    }
+}
+
+/// Lets code written against [`Provide`] be handed a [`Container`] instead,
+/// giving up the compile time checks.
+impl<T: Any + Send + Sync> Provide<Arc<T>> for Container {
+    fn provide(&self) -> anyhow::Result<Arc<T>> {
+        Ok(self.resolve()?)
+    }
+}
+
+impl<T: Any + Send + Sync> Provide<Arc<T>> for Scope<'_> {
+    fn provide(&self) -> anyhow::Result<Arc<T>> {
+        Ok(self.resolve()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct Config {
+        greeting: String,
+    }
+
+    struct Request {
+        id: usize,
+    }
+
+    struct Greeter {
+        config: Arc<Config>,
+        request: Arc<Request>,
+    }
+
+    fn container() -> Container {
+        let requests = AtomicUsize::new(0);
+        Container::new()
+            .with_singleton(|_| {
+                Ok(Config {
+                    greeting: "Hello".to_string(),
+                })
+            })
+            .with_scoped(move |_| {
+                Ok(Request {
+                    id: requests.fetch_add(1, Ordering::SeqCst),
+                })
+            })
+            .with_scoped(|scope| {
+                Ok(Greeter {
+                    config: scope.resolve()?,
+                    request: scope.resolve()?,
+                })
+            })
+    }
+
+    #[test]
+    fn test_singletons_and_scopes() {
+        let container = container();
+
+        let first = container.scope();
+        let greeter = first.resolve::<Greeter>().unwrap();
+        assert_eq!(greeter.config.greeting, "Hello");
+        // Everything in a scope shares its scoped instances
+        assert!(Arc::ptr_eq(&greeter.request, &first.resolve().unwrap()));
+        assert!(Arc::ptr_eq(&greeter, &first.resolve().unwrap()));
+
+        let second = container.scope().resolve::<Greeter>().unwrap();
+        assert!(Arc::ptr_eq(&greeter.config, &second.config));
+        assert_ne!(greeter.request.id, second.request.id);
+    }
+
+    #[test]
+    fn test_instance() {
+        let container = Container::new().with_instance(Config {
+            greeting: "Hi".to_string(),
+        });
+
+        assert_eq!(container.resolve::<Config>().unwrap().greeting, "Hi");
+    }
+
+    #[test]
+    fn test_missing_provider() {
+        let container = Container::new().with_scoped(|scope| {
+            Ok(Greeter {
+                config: scope.resolve()?,
+                request: scope.resolve()?,
+            })
+        });
+
+        assert!(matches!(
+            container.resolve::<Greeter>(),
+            Err(ResolveError::MissingProvider(name)) if name.ends_with("Config")
+        ));
+    }
+
+    #[test]
+    fn test_failed_provider() {
+        let container = Container::new().with_singleton::<Config, _>(|_| {
+            anyhow::bail!("no config file");
+        });
+
+        match container.resolve::<Config>() {
+            Err(ResolveError::Failed { type_name, source }) => {
+                assert!(type_name.ends_with("Config"));
+                assert_eq!(source.to_string(), "no config file");
+            }
+            _ => panic!("expected the provider to fail"),
+        }
+    }
+
+    struct Chicken;
+    struct Egg;
+
+    #[test]
+    fn test_cycle() {
+        let container = Container::new()
+            .with_singleton(|scope| {
+                scope.resolve::<Egg>()?;
+                Ok(Chicken)
+            })
+            .with_scoped(|scope| {
+                scope.resolve::<Chicken>()?;
+                Ok(Egg)
+            });
+
+        match container.resolve::<Chicken>() {
+            Err(ResolveError::Cycle(path)) => {
+                assert_eq!(path.len(), 3);
+                assert!(path[0].ends_with("Chicken"));
+                assert!(path[1].ends_with("Egg"));
+                assert!(path[2].ends_with("Chicken"));
+            }
+            _ => panic!("expected a cycle"),
+        }
+    }
+
+    #[test]
+    fn test_container_provides() {
+        fn greeting<P: Provide<Arc<Config>>>(module: &P) -> String {
+            module.provide().unwrap().greeting.clone()
+        }
+
+        assert_eq!(greeting(&container()), "Hello");
+    }
+}
@@ -1,4 +1,4 @@
-use newtypes::*;
+use std::convert::Infallible;
 use std::str::FromStr;
 
 #[derive(Debug)]
@@ -12,7 +12,7 @@ impl MySqlUsername {
 }
 
 impl FromStr for MySqlUsername {
-    type Err = ImpossibleError;
+    type Err = Infallible;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         Ok(Self(s.to_string()))
@@ -30,7 +30,7 @@ impl MySqlPassword {
 }
 
 impl FromStr for MySqlPassword {
-    type Err = ImpossibleError;
+    type Err = Infallible;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         Ok(Self(s.to_string()))
@@ -48,7 +48,7 @@ impl MySqlAddress {
 }
 
 impl FromStr for MySqlAddress {
-    type Err = ImpossibleError;
+    type Err = Infallible;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         Ok(Self(s.to_string()))
@@ -66,7 +66,7 @@ impl MySqlPort {
 }
 
 impl FromStr for MySqlPort {
-    type Err = ImpossibleError;
+    type Err = Infallible;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         Ok(Self(s.to_string()))
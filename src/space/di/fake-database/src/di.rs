@@ -1,6 +1,16 @@
+use newtype_derive::Newtype;
 use std::error::Error;
 use std::fmt;
 
+pub use crate::error::DatabaseError;
+pub use crate::executor::Executor;
+pub use crate::migrate::{Migration, MigrationError, Migrator};
+pub use crate::pool::{Manager, Pool, PoolConfig, PoolError, PooledConnection};
+pub use crate::row::{FromRow, Row};
+use crate::server::{Connection, Server};
+pub use crate::transaction::Transaction;
+pub use crate::value::{FromValue, Value};
+
 #[derive(Clone, Debug)]
 #[non_exhaustive]
 pub enum EnvironmentError {
@@ -24,42 +34,22 @@ fn get_environment_variable<S: AsRef<str>>(name: S) -> anyhow::Result<String> {
         .map_err(|_| EnvironmentError::MissingParameter(name.as_ref().to_string()))?)
 }
 
-#[derive(Clone)]
+#[derive(Clone, Newtype)]
+#[newtype(from_env = get_environment_variable)]
 struct MySqlUsername(String);
 
-impl MySqlUsername {
-    fn from_env<S: AsRef<str>>(name: S) -> anyhow::Result<Self> {
-        Ok(Self(get_environment_variable(name)?))
-    }
-}
-
-#[derive(Clone)]
+#[derive(Clone, Newtype)]
+#[newtype(from_env = get_environment_variable)]
 struct MySqlPassword(String);
 
-impl MySqlPassword {
-    fn from_env<S: AsRef<str>>(name: S) -> anyhow::Result<Self> {
-        Ok(Self(get_environment_variable(name)?))
-    }
-}
-
-#[derive(Clone)]
+#[derive(Clone, Newtype)]
+#[newtype(from_env = get_environment_variable)]
 struct MySqlAddress(String);
 
-impl MySqlAddress {
-    fn from_env<S: AsRef<str>>(name: S) -> anyhow::Result<Self> {
-        Ok(Self(get_environment_variable(name)?))
-    }
-}
-
-#[derive(Clone)]
+#[derive(Clone, Newtype)]
+#[newtype(from_env = get_environment_variable)]
 struct MySqlPort(u16);
 
-impl MySqlPort {
-    fn from_env<S: AsRef<str>>(name: S) -> anyhow::Result<Self> {
-        Ok(Self(get_environment_variable(name)?.parse()?))
-    }
-}
-
 // The field types are just some newtypes I wrote to encapsulate validation.
 // While we're not covering this pattern today, I couldn't bring myself to make
 // them all Strings after previously explaining why newtypes are so awesome.
@@ -72,6 +62,20 @@ pub struct MySqlConfig {
 }
 
 impl MySqlConfig {
+    pub fn new(
+        address: impl Into<String>,
+        port: u16,
+        username: impl Into<String>,
+        password: impl Into<String>,
+    ) -> Self {
+        Self {
+            address: MySqlAddress(address.into()),
+            port: MySqlPort(port),
+            username: MySqlUsername(username.into()),
+            password: MySqlPassword(password.into()),
+        }
+    }
+
     pub fn from_environment() -> anyhow::Result<Self> {
         Ok(Self {
             address: MySqlAddress::from_env("MYSQL_ADDRESS")?,
@@ -82,25 +86,80 @@ impl MySqlConfig {
     }
 }
 
-#[derive(Copy, Clone, Debug)]
-pub struct MySql {}
+#[derive(Debug)]
+pub struct MySql {
+    connection: Connection,
+}
 
 impl MySql {
     pub fn connect(connection: MySqlConfig) -> anyhow::Result<Self> {
-        let _ = connection.username;
-        let _ = connection.password;
-        let _ = connection.address;
-        let _ = connection.port;
-        Ok(MySql {})
+        // Our fake server lets anyone in
+        let _ = connection.username.0;
+        let _ = connection.password.0;
+        let address = format!("{}:{}", connection.address, connection.port);
+        Ok(MySql {
+            connection: Server::at(address).connect()?,
+        })
+    }
+
+    /// Connects to a brand new server that nothing else can see, which is
+    /// handy for tests that shouldn't trip over each other's data.
+    pub fn in_memory() -> anyhow::Result<Self> {
+        Ok(MySql {
+            connection: Server::default().connect()?,
+        })
+    }
+
+    /// Checks the connection is still alive.
+    pub fn ping(&self) -> anyhow::Result<()> {
+        Ok(self.connection.ping()?)
+    }
+
+    /// Starts a transaction. Nothing done through it is visible to anyone
+    /// else until it's committed, and it's rolled back if dropped without
+    /// being committed.
+    pub fn begin(&self) -> anyhow::Result<Transaction<'_>> {
+        Transaction::begin(&self.connection)
     }
 
-    pub fn query<Q, P>(&self, query: Q, parameters: &[P]) -> anyhow::Result<()>
+    /// Runs `f` inside a transaction, committing if it succeeds and rolling
+    /// back if it returns an error.
+    pub fn transaction<T, F>(&self, f: F) -> anyhow::Result<T>
+    where
+        F: FnOnce(&Transaction<'_>) -> anyhow::Result<T>,
+    {
+        Transaction::run(&self.connection, f)
+    }
+}
+
+impl Executor for MySql {
+    fn query<Q>(&self, query: Q, parameters: &[Value]) -> anyhow::Result<Vec<Row>>
     where
         Q: AsRef<str>,
-        P: AsRef<str>,
     {
-        let _ = query;
-        let _ = parameters;
-        Ok(())
+        self.connection.query(query, parameters)
+    }
+
+    fn execute<Q>(&self, query: Q, parameters: &[Value]) -> anyhow::Result<usize>
+    where
+        Q: AsRef<str>,
+    {
+        self.connection.execute(query, parameters)
+    }
+}
+
+/// A pool of [`MySql`] connections, opened using the [`MySqlConfig`] it was
+/// created with.
+pub type MySqlPool = Pool<MySqlConfig>;
+
+impl Manager for MySqlConfig {
+    type Connection = MySql;
+
+    fn connect(&self) -> anyhow::Result<Self::Connection> {
+        MySql::connect(self.clone())
+    }
+
+    fn is_healthy(&self, connection: &Self::Connection) -> bool {
+        connection.ping().is_ok()
     }
 }
@@ -0,0 +1,946 @@
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use crate::error::DatabaseError;
+use crate::row::Row;
+use crate::sql::{
+    self, Alteration, ColumnDefinition, Columns, Expression, Filter, NewValue, Statement,
+};
+use crate::value::Value;
+
+#[derive(Clone, Debug)]
+struct Table {
+    name: String,
+    columns: Vec<ColumnDefinition>,
+    names: Arc<[String]>,
+    /// Every set of columns whose values, taken together, must be unique
+    unique_keys: Vec<Vec<usize>>,
+    rows: Vec<Vec<Value>>,
+    next_id: i64,
+}
+
+impl Table {
+    fn new(
+        name: String,
+        columns: Vec<ColumnDefinition>,
+        unique_keys: Vec<Vec<String>>,
+    ) -> Result<Self, DatabaseError> {
+        let names: Arc<[String]> = columns.iter().map(|column| column.name.clone()).collect();
+        let mut table = Self {
+            name,
+            columns,
+            names,
+            unique_keys: Vec::new(),
+            rows: Vec::new(),
+            next_id: 1,
+        };
+
+        table.unique_keys = table
+            .columns
+            .iter()
+            .enumerate()
+            .filter(|(_, column)| column.unique || column.primary_key)
+            .map(|(index, _)| vec![index])
+            .collect();
+        for key in unique_keys {
+            let key = key
+                .iter()
+                .map(|column| table.column_index(column))
+                .collect::<Result<_, _>>()?;
+            table.unique_keys.push(key);
+        }
+
+        Ok(table)
+    }
+
+    fn column_index(&self, column: &str) -> Result<usize, DatabaseError> {
+        self.names
+            .iter()
+            .position(|name| name == column)
+            .ok_or_else(|| DatabaseError::NoSuchColumn(column.to_string()))
+    }
+
+    /// Turns a `WHERE` clause into column indices and the values they must
+    /// equal.
+    fn resolve_filter(
+        &self,
+        filter: &Filter,
+        parameters: &[Value],
+    ) -> Result<Vec<(usize, Value)>, DatabaseError> {
+        filter
+            .iter()
+            .map(|(column, expression)| {
+                Ok((self.column_index(column)?, expression.resolve(parameters)))
+            })
+            .collect()
+    }
+
+    /// Type checks a value destined for `index`, rejecting `NULL`s where
+    /// they're not allowed.
+    fn check_value(&self, index: usize, value: Value) -> Result<Value, DatabaseError> {
+        let column = &self.columns[index];
+        let value = column.column_type.coerce(value)?;
+        if value.is_null() && !column.nullable {
+            return Err(DatabaseError::NotNull {
+                table: self.name.clone(),
+                column: column.name.clone(),
+            });
+        }
+        Ok(value)
+    }
+
+    /// Makes sure no two rows share values for a `UNIQUE` or `PRIMARY KEY`
+    /// constraint. `NULL`s never clash with each other.
+    fn check_unique(&self, rows: &[Vec<Value>]) -> Result<(), DatabaseError> {
+        for key in &self.unique_keys {
+            let mut seen = HashSet::new();
+            for row in rows {
+                let values: Vec<&Value> = key.iter().map(|&index| &row[index]).collect();
+                if values.iter().any(|value| value.is_null()) {
+                    continue;
+                }
+                if !seen.insert(values) {
+                    let columns: Vec<&str> = key
+                        .iter()
+                        .map(|&index| self.names[index].as_str())
+                        .collect();
+                    return Err(DatabaseError::UniqueViolation {
+                        table: self.name.clone(),
+                        column: columns.join(", "),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn insert(
+        &mut self,
+        columns: &[String],
+        values: Vec<Vec<Value>>,
+    ) -> Result<usize, DatabaseError> {
+        let indices = columns
+            .iter()
+            .map(|column| self.column_index(column))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut rows = self.rows.clone();
+        let mut next_id = self.next_id;
+        for values in values {
+            let mut row: Vec<Option<Value>> = vec![None; self.columns.len()];
+            for (&index, value) in indices.iter().zip(values) {
+                row[index] = Some(value);
+            }
+
+            let row = row
+                .into_iter()
+                .enumerate()
+                .map(|(index, value)| {
+                    let column = &self.columns[index];
+                    let value = match value {
+                        Some(Value::Null) | None if column.auto_increment => {
+                            next_id += 1;
+                            Value::Int(next_id - 1)
+                        }
+                        Some(value) => value,
+                        None => column.default.clone().unwrap_or(Value::Null),
+                    };
+                    if let (true, Value::Int(id)) = (column.auto_increment, &value) {
+                        next_id = next_id.max(id + 1);
+                    }
+                    self.check_value(index, value)
+                })
+                .collect::<Result<_, _>>()?;
+            rows.push(row);
+        }
+
+        self.check_unique(&rows)?;
+
+        let inserted = rows.len() - self.rows.len();
+        self.rows = rows;
+        self.next_id = next_id;
+        Ok(inserted)
+    }
+
+    fn select(
+        &self,
+        columns: &Columns,
+        filter: &[(usize, Value)],
+    ) -> Result<Vec<Row>, DatabaseError> {
+        let (names, indices): (Arc<[String]>, Vec<usize>) = match columns {
+            Columns::All => (self.names.clone(), (0..self.names.len()).collect()),
+            Columns::Named(names) => (
+                names.as_slice().into(),
+                names
+                    .iter()
+                    .map(|name| self.column_index(name))
+                    .collect::<Result<_, _>>()?,
+            ),
+        };
+
+        Ok(self
+            .rows
+            .iter()
+            .filter(|row| matches(row, filter))
+            .map(|row| {
+                let values = indices.iter().map(|&index| row[index].clone()).collect();
+                Row::new(names.clone(), values)
+            })
+            .collect())
+    }
+
+    fn update(
+        &mut self,
+        assignments: &[(String, NewValue)],
+        parameters: &[Value],
+        filter: &[(usize, Value)],
+    ) -> Result<usize, DatabaseError> {
+        // Either the new value, or the column to lower case for it
+        let assignments = assignments
+            .iter()
+            .map(|(column, value)| {
+                let value = match value {
+                    NewValue::Expression(expression) => Ok(expression.resolve(parameters)),
+                    NewValue::Lower(source) => Err(self.column_index(source)?),
+                };
+                Ok((self.column_index(column)?, value))
+            })
+            .collect::<Result<Vec<_>, DatabaseError>>()?;
+
+        let mut rows = self.rows.clone();
+        let mut updated = 0;
+        for row in rows.iter_mut().filter(|row| matches(row, filter)) {
+            // Every value is worked out from the row as it was before the update
+            let values = assignments
+                .iter()
+                .map(|(index, value)| {
+                    let value = match value {
+                        Ok(value) => value.clone(),
+                        Err(source) => lower(&row[*source])?,
+                    };
+                    self.check_value(*index, value)
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            for (&(index, _), value) in assignments.iter().zip(values) {
+                row[index] = value;
+            }
+            updated += 1;
+        }
+
+        self.check_unique(&rows)?;
+
+        self.rows = rows;
+        Ok(updated)
+    }
+
+    fn alter(&mut self, alteration: Alteration) -> Result<(), DatabaseError> {
+        match alteration {
+            Alteration::AddColumn(column) => {
+                if self.column_index(&column.name).is_ok() {
+                    return Err(DatabaseError::Syntax(format!(
+                        "column {} already exists",
+                        column.name
+                    )));
+                }
+                // Rows already there get the default, like they would have
+                // had the column been there when they were inserted
+                let value = column.default.clone().unwrap_or(Value::Null);
+                let index = self.columns.len();
+                if column.unique {
+                    self.unique_keys.push(vec![index]);
+                }
+                self.columns.push(column);
+                self.names = self
+                    .columns
+                    .iter()
+                    .map(|column| column.name.clone())
+                    .collect();
+                // A NOT NULL column with no default is fine while there's
+                // nothing to fill in
+                let value = if self.rows.is_empty() {
+                    value
+                } else {
+                    self.check_value(index, value)?
+                };
+                for row in &mut self.rows {
+                    row.push(value.clone());
+                }
+            }
+            Alteration::DropColumn(name) => {
+                let index = self.column_index(&name)?;
+                self.columns.remove(index);
+                self.names = self
+                    .columns
+                    .iter()
+                    .map(|column| column.name.clone())
+                    .collect();
+                for row in &mut self.rows {
+                    row.remove(index);
+                }
+                // Constraints go with any column they cover
+                self.unique_keys.retain(|key| !key.contains(&index));
+                for key in &mut self.unique_keys {
+                    for column in key.iter_mut().filter(|column| **column > index) {
+                        *column -= 1;
+                    }
+                }
+            }
+            Alteration::AddUnique(names) => {
+                let key = names
+                    .iter()
+                    .map(|column| self.column_index(column))
+                    .collect::<Result<_, _>>()?;
+                self.unique_keys.push(key);
+            }
+            Alteration::AddForeignKey { column, references } => {
+                let index = self.column_index(&column)?;
+                self.columns[index].references = Some(references);
+            }
+        }
+        self.check_unique(&self.rows)
+    }
+
+    fn delete(&mut self, filter: &[(usize, Value)]) -> usize {
+        let before = self.rows.len();
+        self.rows.retain(|row| !matches(row, filter));
+        before - self.rows.len()
+    }
+}
+
+/// `LOWER(value)`, lower casing each character on its own.
+fn lower(value: &Value) -> Result<Value, DatabaseError> {
+    match value {
+        Value::Null => Ok(Value::Null),
+        Value::Text(text) => Ok(Value::Text(
+            text.chars().flat_map(char::to_lowercase).collect(),
+        )),
+        value => Err(DatabaseError::TypeMismatch {
+            expected: "TEXT",
+            found: value.type_name(),
+        }),
+    }
+}
+
+fn matches(row: &[Value], filter: &[(usize, Value)]) -> bool {
+    // Like real SQL, NULL is never equal to anything, not even NULL
+    filter
+        .iter()
+        .all(|(index, value)| !value.is_null() && &row[*index] == value)
+}
+
+/// What running a single statement produced.
+#[derive(Debug, Default)]
+pub(crate) struct Outcome {
+    pub(crate) rows: Vec<Row>,
+    pub(crate) affected: usize,
+}
+
+/// The in-memory storage every fake connection talks to.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Database {
+    tables: HashMap<String, Table>,
+    // Bumped on every change so transactions can tell if they're out of date
+    version: u64,
+}
+
+impl Database {
+    fn table(&self, table: &str) -> Result<&Table, DatabaseError> {
+        self.tables
+            .get(table)
+            .ok_or_else(|| DatabaseError::NoSuchTable(table.to_string()))
+    }
+
+    fn table_mut(&mut self, table: &str) -> Result<&mut Table, DatabaseError> {
+        self.tables
+            .get_mut(table)
+            .ok_or_else(|| DatabaseError::NoSuchTable(table.to_string()))
+    }
+
+    pub(crate) fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// Makes sure every value in a column with a `REFERENCES` constraint
+    /// exists in the column it references.
+    fn check_foreign_keys(&self) -> Result<(), DatabaseError> {
+        for table in self.tables.values() {
+            for (index, column) in table.columns.iter().enumerate() {
+                let Some(references) = &column.references else {
+                    continue;
+                };
+                let violation = || DatabaseError::ForeignKeyViolation {
+                    table: table.name.clone(),
+                    column: column.name.clone(),
+                    references: format!("{}.{}", references.table, references.column),
+                };
+
+                let parent = self.tables.get(&references.table).ok_or_else(violation)?;
+                let parent_index = parent
+                    .column_index(&references.column)
+                    .map_err(|_| violation())?;
+                let keys: HashSet<&Value> =
+                    parent.rows.iter().map(|row| &row[parent_index]).collect();
+
+                if table
+                    .rows
+                    .iter()
+                    .any(|row| !row[index].is_null() && !keys.contains(&row[index]))
+                {
+                    return Err(violation());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn execute(
+        &mut self,
+        sql: &str,
+        parameters: &[Value],
+    ) -> Result<Outcome, DatabaseError> {
+        let query = sql::parse(sql)?;
+
+        if parameters.len() != query.parameters {
+            return Err(DatabaseError::ParameterCount {
+                expected: query.parameters,
+                found: parameters.len(),
+            });
+        }
+
+        if matches!(query.statement, Statement::Select { .. }) {
+            return self.apply(query.statement, parameters);
+        }
+
+        // A write might leave a foreign key dangling, which we can only tell
+        // once it's done, so keep a copy of the tables to go back to
+        let before = self.tables.clone();
+        let outcome = self
+            .apply(query.statement, parameters)
+            .and_then(|outcome| self.check_foreign_keys().map(|_| outcome));
+        match outcome {
+            Ok(outcome) => {
+                self.version += 1;
+                Ok(outcome)
+            }
+            Err(error) => {
+                self.tables = before;
+                Err(error)
+            }
+        }
+    }
+
+    fn apply(
+        &mut self,
+        statement: Statement,
+        parameters: &[Value],
+    ) -> Result<Outcome, DatabaseError> {
+        let resolve = |expressions: &[Expression]| -> Vec<Value> {
+            expressions
+                .iter()
+                .map(|expression| expression.resolve(parameters))
+                .collect()
+        };
+
+        let mut outcome = Outcome::default();
+        match statement {
+            Statement::CreateTable {
+                table,
+                if_not_exists,
+                columns,
+                unique_keys,
+            } => match self.tables.entry(table) {
+                Entry::Occupied(entry) => {
+                    if !if_not_exists {
+                        return Err(DatabaseError::TableExists(entry.key().clone()));
+                    }
+                }
+                Entry::Vacant(entry) => {
+                    let new_table = Table::new(entry.key().clone(), columns, unique_keys)?;
+                    entry.insert(new_table);
+                }
+            },
+            Statement::AlterTable { table, alteration } => {
+                self.table_mut(&table)?.alter(alteration)?;
+            }
+            Statement::DropTable { table, if_exists } => {
+                if self.tables.remove(&table).is_none() && !if_exists {
+                    return Err(DatabaseError::NoSuchTable(table));
+                }
+            }
+            Statement::Insert {
+                table,
+                columns,
+                rows,
+            } => {
+                let rows = rows.iter().map(|row| resolve(row)).collect();
+                outcome.affected = self.table_mut(&table)?.insert(&columns, rows)?;
+            }
+            Statement::Select {
+                table,
+                columns,
+                filter,
+            } => {
+                let table = self.table(&table)?;
+                let filter = table.resolve_filter(&filter, parameters)?;
+                outcome.rows = table.select(&columns, &filter)?;
+            }
+            Statement::Update {
+                table,
+                assignments,
+                filter,
+            } => {
+                let table = self.table_mut(&table)?;
+                let filter = table.resolve_filter(&filter, parameters)?;
+                outcome.affected = table.update(&assignments, parameters, &filter)?;
+            }
+            Statement::Delete { table, filter } => {
+                let table = self.table_mut(&table)?;
+                let filter = table.resolve_filter(&filter, parameters)?;
+                outcome.affected = table.delete(&filter);
+            }
+        }
+
+        Ok(outcome)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::executor::Executor;
+    use crate::server::{Connection, Server};
+
+    fn users() -> Connection {
+        let connection = Server::default().connect().unwrap();
+        connection
+            .execute(
+                "
+                    CREATE TABLE users (
+                      id INT PRIMARY KEY AUTO_INCREMENT,
+                      email_address TEXT NOT NULL UNIQUE,
+                      username TEXT NOT NULL UNIQUE
+                    )
+                ",
+                &[],
+            )
+            .unwrap();
+        connection
+            .execute(
+                "INSERT INTO users (email_address, username) VALUES (?, ?), (?, ?)",
+                &[
+                    "daniel@example.com".into(),
+                    "Daniel".into(),
+                    "yuki@example.com".into(),
+                    "Yuki".into(),
+                ],
+            )
+            .unwrap();
+        connection
+    }
+
+    fn database_error(error: anyhow::Error) -> DatabaseError {
+        error
+            .downcast::<DatabaseError>()
+            .expect("expected a DatabaseError")
+    }
+
+    #[test]
+    fn test_insert_then_select() {
+        let connection = users();
+
+        let rows = connection
+            .query(
+                "SELECT id, username FROM users WHERE email_address = ?",
+                &["yuki@example.com".into()],
+            )
+            .unwrap();
+
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].columns(), ["id", "username"]);
+        assert_eq!(rows[0].get::<i64, _>("id").unwrap(), 2);
+        assert_eq!(rows[0].get::<String, _>("username").unwrap(), "Yuki");
+
+        let rows = connection.query("SELECT * FROM users", &[]).unwrap();
+        assert_eq!(rows.len(), 2);
+    }
+
+    #[test]
+    fn test_unique_constraints() {
+        let connection = users();
+
+        let error = connection
+            .execute(
+                "INSERT INTO users (email_address, username) VALUES (?, ?)",
+                &["daniel@example.com".into(), "Danny".into()],
+            )
+            .unwrap_err();
+        assert_eq!(
+            database_error(error),
+            DatabaseError::UniqueViolation {
+                table: "users".to_string(),
+                column: "email_address".to_string(),
+            }
+        );
+
+        let error = connection
+            .execute(
+                "UPDATE users SET username = ? WHERE username = ?",
+                &["Daniel".into(), "Yuki".into()],
+            )
+            .unwrap_err();
+        assert_eq!(
+            database_error(error),
+            DatabaseError::UniqueViolation {
+                table: "users".to_string(),
+                column: "username".to_string(),
+            }
+        );
+
+        // Nothing was changed by the failed statements
+        assert_eq!(
+            connection.query("SELECT * FROM users", &[]).unwrap().len(),
+            2
+        );
+        assert_eq!(
+            connection
+                .query("SELECT * FROM users WHERE username = 'Yuki'", &[])
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_statements_are_atomic() {
+        let connection = users();
+
+        // The second row clashes with the first, so neither is inserted
+        let error = connection
+            .execute(
+                "INSERT INTO users (email_address, username) VALUES (?, ?), (?, ?)",
+                &[
+                    "ted@example.com".into(),
+                    "Ted".into(),
+                    "ted@example.com".into(),
+                    "Teddy".into(),
+                ],
+            )
+            .unwrap_err();
+        assert!(matches!(
+            database_error(error),
+            DatabaseError::UniqueViolation { .. }
+        ));
+        assert_eq!(
+            connection.query("SELECT * FROM users", &[]).unwrap().len(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_update_and_delete() {
+        let connection = users();
+
+        let updated = connection
+            .execute(
+                "UPDATE users SET email_address = ? WHERE username = ?",
+                &["daniel@example.org".into(), "Daniel".into()],
+            )
+            .unwrap();
+        assert_eq!(updated, 1);
+
+        let rows = connection
+            .query(
+                "SELECT username FROM users WHERE email_address = ?",
+                &["daniel@example.org".into()],
+            )
+            .unwrap();
+        assert_eq!(rows[0].get::<String, _>("username").unwrap(), "Daniel");
+
+        let deleted = connection
+            .execute("DELETE FROM users WHERE username = ?", &["Daniel".into()])
+            .unwrap();
+        assert_eq!(deleted, 1);
+        assert_eq!(
+            connection.query("SELECT * FROM users", &[]).unwrap().len(),
+            1
+        );
+
+        assert_eq!(connection.execute("DELETE FROM users", &[]).unwrap(), 1);
+        assert!(
+            connection
+                .query("SELECT * FROM users", &[])
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_types_nulls_and_defaults() {
+        let connection = Server::default().connect().unwrap();
+        connection
+            .execute(
+                "CREATE TABLE pets (name TEXT NOT NULL, age INT, indoor BOOL DEFAULT TRUE)",
+                &[],
+            )
+            .unwrap();
+
+        let error = connection
+            .execute(
+                "INSERT INTO pets (name, age) VALUES (?, ?)",
+                &["Yuki".into(), "old".into()],
+            )
+            .unwrap_err();
+        assert_eq!(
+            database_error(error),
+            DatabaseError::TypeMismatch {
+                expected: "INT",
+                found: "TEXT"
+            }
+        );
+
+        let error = connection
+            .execute("INSERT INTO pets (age) VALUES (3)", &[])
+            .unwrap_err();
+        assert_eq!(
+            database_error(error),
+            DatabaseError::NotNull {
+                table: "pets".to_string(),
+                column: "name".to_string(),
+            }
+        );
+
+        connection
+            .execute("INSERT INTO pets (name) VALUES ('Yuki')", &[])
+            .unwrap();
+        let rows = connection.query("SELECT * FROM pets", &[]).unwrap();
+        assert_eq!(rows[0].get::<Option<i64>, _>("age").unwrap(), None);
+        assert!(rows[0].get::<bool, _>("indoor").unwrap());
+    }
+
+    #[test]
+    fn test_create_and_drop_table() {
+        let connection = users();
+
+        let error = connection
+            .execute("CREATE TABLE users (id INT)", &[])
+            .unwrap_err();
+        assert_eq!(
+            database_error(error),
+            DatabaseError::TableExists("users".to_string())
+        );
+        connection
+            .execute("CREATE TABLE IF NOT EXISTS users (id INT)", &[])
+            .unwrap();
+
+        connection.execute("DROP TABLE users", &[]).unwrap();
+        let error = connection.query("SELECT * FROM users", &[]).unwrap_err();
+        assert_eq!(
+            database_error(error),
+            DatabaseError::NoSuchTable("users".to_string())
+        );
+        connection
+            .execute("DROP TABLE IF EXISTS users", &[])
+            .unwrap();
+    }
+
+    #[test]
+    fn test_parameter_count_is_checked() {
+        let connection = users();
+
+        let error = connection
+            .execute(
+                "INSERT INTO users (email_address, username) VALUES (?, ?)",
+                &["ted@example.com".into()],
+            )
+            .unwrap_err();
+
+        assert_eq!(
+            database_error(error),
+            DatabaseError::ParameterCount {
+                expected: 2,
+                found: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_foreign_keys() {
+        let connection = users();
+        connection
+            .execute(
+                "
+                    CREATE TABLE pets (
+                      carer TEXT NOT NULL REFERENCES users (username),
+                      name TEXT NOT NULL,
+                      UNIQUE (carer, name)
+                    )
+                ",
+                &[],
+            )
+            .unwrap();
+        let violation = DatabaseError::ForeignKeyViolation {
+            table: "pets".to_string(),
+            column: "carer".to_string(),
+            references: "users.username".to_string(),
+        };
+
+        connection
+            .execute(
+                "INSERT INTO pets (carer, name) VALUES ('Daniel', 'Yuki')",
+                &[],
+            )
+            .unwrap();
+        let error = connection
+            .execute("INSERT INTO pets (carer, name) VALUES ('Ted', 'Yuki')", &[])
+            .unwrap_err();
+        assert_eq!(database_error(error), violation);
+
+        let error = connection
+            .execute("UPDATE pets SET carer = 'Ted' WHERE name = 'Yuki'", &[])
+            .unwrap_err();
+        assert_eq!(database_error(error), violation);
+
+        // Users with pets can't be removed out from under them
+        let error = connection
+            .execute("DELETE FROM users WHERE username = 'Daniel'", &[])
+            .unwrap_err();
+        assert_eq!(database_error(error), violation);
+        let error = connection.execute("DROP TABLE users", &[]).unwrap_err();
+        assert_eq!(database_error(error), violation);
+        assert_eq!(
+            connection.query("SELECT * FROM users", &[]).unwrap().len(),
+            2
+        );
+
+        // Users without pets can though
+        connection
+            .execute("DELETE FROM users WHERE username = 'Yuki'", &[])
+            .unwrap();
+
+        let error = connection
+            .execute("CREATE TABLE toys (owner TEXT REFERENCES cats (name))", &[])
+            .unwrap_err();
+        assert!(matches!(
+            database_error(error),
+            DatabaseError::ForeignKeyViolation { .. }
+        ));
+        assert!(connection.query("SELECT * FROM toys", &[]).is_err());
+    }
+
+    #[test]
+    fn test_composite_unique_keys() {
+        let connection = users();
+        connection
+            .execute(
+                "CREATE TABLE pets (carer TEXT NOT NULL, name TEXT NOT NULL, PRIMARY KEY (carer, name))",
+                &[],
+            )
+            .unwrap();
+
+        connection
+            .execute(
+                "INSERT INTO pets (carer, name) VALUES ('Daniel', 'Yuki'), ('Yuki', 'Yuki')",
+                &[],
+            )
+            .unwrap();
+        let error = connection
+            .execute(
+                "INSERT INTO pets (carer, name) VALUES ('Daniel', 'Yuki')",
+                &[],
+            )
+            .unwrap_err();
+        assert_eq!(
+            database_error(error),
+            DatabaseError::UniqueViolation {
+                table: "pets".to_string(),
+                column: "carer, name".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_alter_table() {
+        let connection = users();
+        connection
+            .execute(
+                "ALTER TABLE users ADD COLUMN username_key TEXT NOT NULL DEFAULT ''",
+                &[],
+            )
+            .unwrap();
+        connection
+            .execute("UPDATE users SET username_key = LOWER(username)", &[])
+            .unwrap();
+        connection
+            .execute("ALTER TABLE users ADD UNIQUE (username_key)", &[])
+            .unwrap();
+
+        let rows = connection
+            .query(
+                "SELECT username FROM users WHERE username_key = ?",
+                &["yuki".into()],
+            )
+            .unwrap();
+        assert_eq!(rows[0].get::<String, _>("username").unwrap(), "Yuki");
+        let error = connection
+            .execute(
+                "INSERT INTO users (email_address, username, username_key) VALUES (?, ?, ?)",
+                &["yuki2@example.com".into(), "YUKI".into(), "yuki".into()],
+            )
+            .unwrap_err();
+        assert_eq!(
+            database_error(error),
+            DatabaseError::UniqueViolation {
+                table: "users".to_string(),
+                column: "username_key".to_string(),
+            }
+        );
+
+        connection
+            .execute("CREATE TABLE pets (carer TEXT, name TEXT)", &[])
+            .unwrap();
+        connection
+            .execute(
+                "ALTER TABLE pets ADD FOREIGN KEY (carer) REFERENCES users (username_key)",
+                &[],
+            )
+            .unwrap();
+        let error = connection
+            .execute(
+                "INSERT INTO pets (carer, name) VALUES ('Yuki', 'Mochi')",
+                &[],
+            )
+            .unwrap_err();
+        assert!(matches!(
+            database_error(error),
+            DatabaseError::ForeignKeyViolation { .. }
+        ));
+
+        // Nothing to fill in a NOT NULL column without a default with
+        let error = connection
+            .execute("ALTER TABLE users ADD COLUMN age INT NOT NULL", &[])
+            .unwrap_err();
+        assert!(matches!(
+            database_error(error),
+            DatabaseError::NotNull { .. }
+        ));
+
+        connection.execute("DROP TABLE pets", &[]).unwrap();
+        connection
+            .execute("ALTER TABLE users DROP COLUMN username_key", &[])
+            .unwrap();
+        let rows = connection.query("SELECT * FROM users", &[]).unwrap();
+        assert_eq!(rows[0].columns(), ["id", "email_address", "username"]);
+        // The constraints on the other columns are still there
+        let error = connection
+            .execute(
+                "INSERT INTO users (email_address, username) VALUES ('yuki@example.com', 'Yuki')",
+                &[],
+            )
+            .unwrap_err();
+        assert!(matches!(
+            database_error(error),
+            DatabaseError::UniqueViolation { .. }
+        ));
+    }
+}
@@ -0,0 +1,89 @@
+use std::error::Error;
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum DatabaseError {
+    TooManyConnections {
+        limit: usize,
+    },
+    ConnectionLost,
+    TransactionConflict,
+    Syntax(String),
+    NoSuchTable(String),
+    TableExists(String),
+    NoSuchColumn(String),
+    UniqueViolation {
+        table: String,
+        column: String,
+    },
+    NotNull {
+        table: String,
+        column: String,
+    },
+    ForeignKeyViolation {
+        table: String,
+        column: String,
+        references: String,
+    },
+    ParameterCount {
+        expected: usize,
+        found: usize,
+    },
+    ColumnCount {
+        expected: usize,
+        found: usize,
+    },
+    TypeMismatch {
+        expected: &'static str,
+        found: &'static str,
+    },
+    OutOfRange {
+        value: i64,
+        target: &'static str,
+    },
+}
+
+impl fmt::Display for DatabaseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooManyConnections { limit } => {
+                write!(f, "Too many connections, the limit is {limit}")
+            }
+            Self::ConnectionLost => write!(f, "Lost connection to the server"),
+            Self::TransactionConflict => {
+                write!(
+                    f,
+                    "Transaction conflicted with a concurrent change, try again"
+                )
+            }
+            Self::Syntax(message) => write!(f, "Syntax error: {message}"),
+            Self::NoSuchTable(table) => write!(f, "Table {table} does not exist"),
+            Self::TableExists(table) => write!(f, "Table {table} already exists"),
+            Self::NoSuchColumn(column) => write!(f, "Column {column} does not exist"),
+            Self::UniqueViolation { table, column } => {
+                write!(f, "Duplicate entry for {table}.{column}")
+            }
+            Self::NotNull { table, column } => write!(f, "{table}.{column} cannot be NULL"),
+            Self::ForeignKeyViolation {
+                table,
+                column,
+                references,
+            } => write!(f, "{table}.{column} must match an existing {references}"),
+            Self::ParameterCount { expected, found } => {
+                write!(f, "Expected {expected} parameters, found {found}")
+            }
+            Self::ColumnCount { expected, found } => {
+                write!(f, "Expected {expected} values, found {found}")
+            }
+            Self::TypeMismatch { expected, found } => {
+                write!(f, "Expected a value of type {expected}, found {found}")
+            }
+            Self::OutOfRange { value, target } => {
+                write!(f, "Value {value} does not fit in {target}")
+            }
+        }
+    }
+}
+
+impl Error for DatabaseError {}
@@ -0,0 +1,28 @@
+use crate::row::{FromRow, Row};
+use crate::value::Value;
+
+/// Anything queries can be run against, such as a connection or a
+/// transaction. Writing store methods against this rather than a concrete
+/// connection lets the caller decide whether they run inside a transaction.
+pub trait Executor {
+    fn query<Q>(&self, query: Q, parameters: &[Value]) -> anyhow::Result<Vec<Row>>
+    where
+        Q: AsRef<str>;
+
+    /// Runs a statement that doesn't return rows, giving back the number of
+    /// rows it inserted, updated or deleted.
+    fn execute<Q>(&self, query: Q, parameters: &[Value]) -> anyhow::Result<usize>
+    where
+        Q: AsRef<str>;
+
+    fn query_as<T, Q>(&self, query: Q, parameters: &[Value]) -> anyhow::Result<Vec<T>>
+    where
+        T: FromRow,
+        Q: AsRef<str>,
+    {
+        self.query(query, parameters)?
+            .iter()
+            .map(T::from_row)
+            .collect()
+    }
+}
@@ -1,2 +1,13 @@
+mod engine;
+mod sql;
+
 pub mod di;
+pub mod error;
+pub mod executor;
+pub mod migrate;
 pub mod nondi;
+pub mod pool;
+pub mod row;
+pub mod server;
+pub mod transaction;
+pub mod value;
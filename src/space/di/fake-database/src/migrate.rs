@@ -0,0 +1,351 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::di::MySql;
+use crate::executor::Executor;
+use crate::sql;
+
+/// One versioned change to a database's schema, with the script that makes
+/// it and the script that undoes it. The scripts are usually embedded with
+/// `include_str!` so the binary always carries the schema it expects.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Migration {
+    pub version: u32,
+    pub name: &'static str,
+    pub up: &'static str,
+    pub down: &'static str,
+}
+
+impl Migration {
+    /// A fingerprint of the `up` script, recorded when the migration is
+    /// applied so we can tell if it was edited afterwards.
+    pub fn checksum(&self) -> String {
+        // FNV-1a, nothing clever but plenty to spot an edited script
+        let hash = self.up.bytes().fold(0xcbf29ce484222325_u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+        });
+        format!("{hash:016x}")
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum MigrationError {
+    /// An applied migration's script no longer matches what was run.
+    ChecksumMismatch { version: u32, name: String },
+    /// The database has a migration applied that we know nothing about,
+    /// probably from a newer version of the application.
+    UnknownMigration { version: u32 },
+    /// A migration that hasn't been applied is older than one that has, so
+    /// applying it now could run it against a schema it wasn't written for.
+    OutOfOrder { version: u32, latest: u32 },
+}
+
+impl fmt::Display for MigrationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ChecksumMismatch { version, name } => write!(
+                f,
+                "Migration {version} ({name}) has changed since it was applied"
+            ),
+            Self::UnknownMigration { version } => {
+                write!(f, "Migration {version} was applied but is not known")
+            }
+            Self::OutOfOrder { version, latest } => write!(
+                f,
+                "Migration {version} is older than the latest applied migration {latest}"
+            ),
+        }
+    }
+}
+
+impl Error for MigrationError {}
+
+/// Brings a database's schema up to date with a set of [`Migration`]s,
+/// keeping track of which have been applied in a `migrations` table.
+#[derive(Clone, Debug)]
+pub struct Migrator {
+    migrations: Vec<Migration>,
+}
+
+impl Migrator {
+    /// # Panics
+    ///
+    /// If two migrations share a version.
+    pub fn new(migrations: &[Migration]) -> Self {
+        let mut migrations = migrations.to_vec();
+        migrations.sort_by_key(|migration| migration.version);
+        for pair in migrations.windows(2) {
+            assert_ne!(
+                pair[0].version, pair[1].version,
+                "migrations {} and {} share a version",
+                pair[0].name, pair[1].name
+            );
+        }
+        Self { migrations }
+    }
+
+    fn create_table(mysql: &MySql) -> anyhow::Result<()> {
+        mysql.execute(
+            "
+                CREATE TABLE IF NOT EXISTS migrations (
+                  version INT PRIMARY KEY,
+                  name VARCHAR(255) NOT NULL,
+                  checksum VARCHAR(16) NOT NULL
+                )
+            ",
+            &[],
+        )?;
+        Ok(())
+    }
+
+    /// The versions of the migrations applied to the database, oldest first.
+    pub fn applied(&self, mysql: &MySql) -> anyhow::Result<Vec<u32>> {
+        Ok(self
+            .check(mysql)?
+            .iter()
+            .map(|migration| migration.version)
+            .collect())
+    }
+
+    /// Makes sure every applied migration is one we know, unchanged, and that
+    /// nothing pending is older than what's already applied. Returns the
+    /// applied migrations, oldest first.
+    fn check(&self, mysql: &MySql) -> anyhow::Result<Vec<&Migration>> {
+        Self::create_table(mysql)?;
+
+        let mut rows = mysql.query("SELECT version, checksum FROM migrations", &[])?;
+        rows.sort_by_key(|row| row.get::<u32, _>("version").ok());
+
+        let mut applied = Vec::new();
+        for row in rows {
+            let version = row.get("version")?;
+            let checksum: String = row.get("checksum")?;
+            let migration = self
+                .migrations
+                .iter()
+                .find(|migration| migration.version == version)
+                .ok_or(MigrationError::UnknownMigration { version })?;
+            if migration.checksum() != checksum {
+                return Err(MigrationError::ChecksumMismatch {
+                    version,
+                    name: migration.name.to_string(),
+                }
+                .into());
+            }
+            applied.push(migration);
+        }
+
+        if let Some(latest) = applied.last().map(|migration| migration.version) {
+            let skipped = self
+                .migrations
+                .iter()
+                .filter(|migration| migration.version < latest)
+                .find(|migration| !applied.contains(migration));
+            if let Some(migration) = skipped {
+                return Err(MigrationError::OutOfOrder {
+                    version: migration.version,
+                    latest,
+                }
+                .into());
+            }
+        }
+
+        Ok(applied)
+    }
+
+    /// Applies every pending migration, oldest first, returning the versions
+    /// applied. Each migration runs in its own transaction, so one that fails
+    /// leaves the database as the previous migration left it.
+    pub fn up(&self, mysql: &MySql) -> anyhow::Result<Vec<u32>> {
+        let latest = self.check(mysql)?.last().map(|migration| migration.version);
+
+        let mut versions = Vec::new();
+        for migration in &self.migrations {
+            if latest.is_some_and(|latest| migration.version <= latest) {
+                continue;
+            }
+            mysql.transaction(|transaction| {
+                for statement in sql::split(migration.up)? {
+                    transaction.execute(statement, &[])?;
+                }
+                transaction.execute(
+                    "INSERT INTO migrations (version, name, checksum) VALUES (?, ?, ?)",
+                    &[
+                        migration.version.into(),
+                        migration.name.into(),
+                        migration.checksum().into(),
+                    ],
+                )
+            })?;
+            versions.push(migration.version);
+        }
+        Ok(versions)
+    }
+
+    /// Reverts applied migrations, newest first, until none newer than
+    /// `target` remain, returning the versions reverted. A `target` of `0`
+    /// reverts everything.
+    pub fn down(&self, mysql: &MySql, target: u32) -> anyhow::Result<Vec<u32>> {
+        let applied = self.check(mysql)?;
+
+        let mut versions = Vec::new();
+        for migration in applied.into_iter().rev() {
+            if migration.version <= target {
+                break;
+            }
+            mysql.transaction(|transaction| {
+                for statement in sql::split(migration.down)? {
+                    transaction.execute(statement, &[])?;
+                }
+                transaction.execute(
+                    "DELETE FROM migrations WHERE version = ?",
+                    &[migration.version.into()],
+                )
+            })?;
+            versions.push(migration.version);
+        }
+        Ok(versions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::DatabaseError;
+
+    const USERS: Migration = Migration {
+        version: 1,
+        name: "create_users",
+        up: "CREATE TABLE users (username TEXT PRIMARY KEY);",
+        down: "DROP TABLE users;",
+    };
+
+    const PETS: Migration = Migration {
+        version: 2,
+        name: "create_pets",
+        up: "
+            CREATE TABLE pets (
+              carer TEXT NOT NULL REFERENCES users (username),
+              name TEXT NOT NULL
+            );
+            -- Everyone starts off with a cat
+            INSERT INTO users (username) VALUES ('Daniel');
+            INSERT INTO pets (carer, name) VALUES ('Daniel', 'Yuki');
+        ",
+        down: "DROP TABLE pets;",
+    };
+
+    fn tables(mysql: &MySql) -> Vec<&'static str> {
+        ["users", "pets"]
+            .into_iter()
+            .filter(|table| mysql.query(format!("SELECT * FROM {table}"), &[]).is_ok())
+            .collect()
+    }
+
+    #[test]
+    fn test_up_and_down() {
+        let mysql = MySql::in_memory().unwrap();
+        let migrator = Migrator::new(&[PETS, USERS]);
+
+        assert_eq!(migrator.up(&mysql).unwrap(), vec![1, 2]);
+        assert_eq!(migrator.applied(&mysql).unwrap(), vec![1, 2]);
+        assert_eq!(tables(&mysql), vec!["users", "pets"]);
+        assert_eq!(mysql.query("SELECT * FROM pets", &[]).unwrap().len(), 1);
+
+        // Running again has nothing left to do
+        assert_eq!(migrator.up(&mysql).unwrap(), Vec::<u32>::new());
+
+        assert_eq!(migrator.down(&mysql, 1).unwrap(), vec![2]);
+        assert_eq!(tables(&mysql), vec!["users"]);
+        assert_eq!(migrator.down(&mysql, 0).unwrap(), vec![1]);
+        assert_eq!(tables(&mysql), Vec::<&str>::new());
+        assert_eq!(migrator.applied(&mysql).unwrap(), Vec::<u32>::new());
+    }
+
+    #[test]
+    fn test_new_migrations_are_applied() {
+        let mysql = MySql::in_memory().unwrap();
+
+        assert_eq!(Migrator::new(&[USERS]).up(&mysql).unwrap(), vec![1]);
+        assert_eq!(Migrator::new(&[USERS, PETS]).up(&mysql).unwrap(), vec![2]);
+    }
+
+    #[test]
+    fn test_failed_migration_is_rolled_back() {
+        let mysql = MySql::in_memory().unwrap();
+        let broken = Migration {
+            up: "CREATE TABLE pets (name TEXT); INSERT INTO nowhere (name) VALUES ('Yuki');",
+            ..PETS
+        };
+
+        let error = Migrator::new(&[USERS, broken]).up(&mysql).unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<DatabaseError>(),
+            Some(&DatabaseError::NoSuchTable("nowhere".to_string()))
+        );
+        // The first migration stuck, the second left nothing behind
+        assert_eq!(tables(&mysql), vec!["users"]);
+        assert_eq!(Migrator::new(&[USERS]).applied(&mysql).unwrap(), vec![1]);
+    }
+
+    #[test]
+    fn test_changed_migration_is_refused() {
+        let mysql = MySql::in_memory().unwrap();
+        Migrator::new(&[USERS, PETS]).up(&mysql).unwrap();
+
+        let edited = Migration {
+            up: "CREATE TABLE pets (carer TEXT, name TEXT);",
+            ..PETS
+        };
+        let error = Migrator::new(&[USERS, edited]).up(&mysql).unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<MigrationError>(),
+            Some(&MigrationError::ChecksumMismatch {
+                version: 2,
+                name: "create_pets".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_unknown_and_out_of_order_migrations_are_refused() {
+        let mysql = MySql::in_memory().unwrap();
+        Migrator::new(&[USERS, PETS]).up(&mysql).unwrap();
+
+        let error = Migrator::new(&[USERS]).up(&mysql).unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<MigrationError>(),
+            Some(&MigrationError::UnknownMigration { version: 2 })
+        );
+
+        let mysql = MySql::in_memory().unwrap();
+        let later = Migration {
+            version: 3,
+            name: "create_later",
+            up: "CREATE TABLE later (name TEXT);",
+            down: "DROP TABLE later;",
+        };
+        Migrator::new(&[USERS, later]).up(&mysql).unwrap();
+        let error = Migrator::new(&[USERS, PETS, later]).up(&mysql).unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<MigrationError>(),
+            Some(&MigrationError::OutOfOrder {
+                version: 2,
+                latest: 3
+            })
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "share a version")]
+    fn test_duplicate_versions_panic() {
+        Migrator::new(&[
+            USERS,
+            Migration {
+                name: "again",
+                ..USERS
+            },
+        ]);
+    }
+}
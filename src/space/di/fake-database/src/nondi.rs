@@ -1,6 +1,13 @@
+pub use crate::error::DatabaseError;
+pub use crate::executor::Executor;
+pub use crate::row::{FromRow, Row};
+use crate::server::{Connection, Server};
+pub use crate::value::{FromValue, Value};
 
-#[derive(Copy, Clone, Debug)]
-pub struct MySql {}
+#[derive(Debug)]
+pub struct MySql {
+    connection: Connection,
+}
 
 impl MySql {
     pub fn connect<A, U, P>(address: A, port: u8, username: U, password: P) -> anyhow::Result<Self>
@@ -9,21 +16,34 @@ impl MySql {
         U: AsRef<str>,
         P: AsRef<str>,
     {
+        // Our fake server lets anyone in
         let _ = username;
         let _ = password;
-        let _ = address;
-        let _ = port;
+        let address = format!("{}:{port}", address.as_ref());
 
-        Ok(MySql {})
+        Ok(MySql {
+            connection: Server::at(address).connect()?,
+        })
     }
 
-    pub fn query<Q, P>(&self, query: Q, parameters: &[P]) -> anyhow::Result<()>
+    /// Checks the connection is still alive.
+    pub fn ping(&self) -> anyhow::Result<()> {
+        Ok(self.connection.ping()?)
+    }
+}
+
+impl Executor for MySql {
+    fn query<Q>(&self, query: Q, parameters: &[Value]) -> anyhow::Result<Vec<Row>>
+    where
+        Q: AsRef<str>,
+    {
+        self.connection.query(query, parameters)
+    }
+
+    fn execute<Q>(&self, query: Q, parameters: &[Value]) -> anyhow::Result<usize>
     where
         Q: AsRef<str>,
-        P: AsRef<str>,
     {
-        let _ = query;
-        let _ = parameters;
-        Ok(())
+        self.connection.execute(query, parameters)
     }
 }
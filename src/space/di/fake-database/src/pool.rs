@@ -0,0 +1,344 @@
+use std::error::Error;
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use std::sync::{Condvar, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+/// Knows how to open connections for a [`Pool`] and how to tell whether a
+/// connection is still usable.
+pub trait Manager {
+    type Connection;
+
+    fn connect(&self) -> anyhow::Result<Self::Connection>;
+
+    fn is_healthy(&self, connection: &Self::Connection) -> bool;
+}
+
+#[derive(Clone, Debug)]
+pub struct PoolConfig {
+    max_size: usize,
+    checkout_timeout: Duration,
+    test_on_checkout: bool,
+}
+
+impl Default for PoolConfig {
+    fn default() -> Self {
+        Self {
+            max_size: 10,
+            checkout_timeout: Duration::from_secs(30),
+            test_on_checkout: true,
+        }
+    }
+}
+
+impl PoolConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The most connections the pool will have open at once.
+    pub fn with_max_size(mut self, max_size: usize) -> Self {
+        self.max_size = max_size.max(1);
+        self
+    }
+
+    /// How long [`Pool::get`] waits for a connection to be returned when all
+    /// of them are in use.
+    pub fn with_checkout_timeout(mut self, checkout_timeout: Duration) -> Self {
+        self.checkout_timeout = checkout_timeout;
+        self
+    }
+
+    /// Whether idle connections are health checked before being handed out.
+    pub fn with_test_on_checkout(mut self, test_on_checkout: bool) -> Self {
+        self.test_on_checkout = test_on_checkout;
+        self
+    }
+}
+
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum PoolError {
+    Timeout(Duration),
+    Connect(anyhow::Error),
+}
+
+impl fmt::Display for PoolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Timeout(timeout) => {
+                write!(f, "Timed out after {timeout:?} waiting for a connection")
+            }
+            Self::Connect(error) => write!(f, "Could not open a connection: {error}"),
+        }
+    }
+}
+
+impl Error for PoolError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Timeout(_) => None,
+            Self::Connect(error) => Some(error.as_ref()),
+        }
+    }
+}
+
+/// A snapshot of how busy a [`Pool`] is.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct PoolState {
+    pub open: usize,
+    pub idle: usize,
+}
+
+struct Connections<C> {
+    idle: Vec<C>,
+    open: usize,
+}
+
+/// Hands out connections, opening new ones up to a limit and reusing them
+/// once they're returned. Wrap it in an `Arc` to share it between the things
+/// that need a connection.
+pub struct Pool<M: Manager> {
+    manager: M,
+    config: PoolConfig,
+    connections: Mutex<Connections<M::Connection>>,
+    returned: Condvar,
+}
+
+impl<M: Manager> Pool<M> {
+    pub fn new(manager: M, config: PoolConfig) -> Self {
+        Self {
+            manager,
+            config,
+            connections: Mutex::new(Connections {
+                idle: Vec::new(),
+                open: 0,
+            }),
+            returned: Condvar::new(),
+        }
+    }
+
+    fn connections(&self) -> MutexGuard<'_, Connections<M::Connection>> {
+        self.connections.lock().expect("connection pool poisoned")
+    }
+
+    pub fn state(&self) -> PoolState {
+        let connections = self.connections();
+        PoolState {
+            open: connections.open,
+            idle: connections.idle.len(),
+        }
+    }
+
+    /// Checks out a connection, waiting up to the checkout timeout for one to
+    /// become available. The connection goes back in the pool when the
+    /// returned guard is dropped.
+    pub fn get(&self) -> Result<PooledConnection<'_, M>, PoolError> {
+        let deadline = Instant::now() + self.config.checkout_timeout;
+        let mut connections = self.connections();
+
+        loop {
+            if let Some(connection) = connections.idle.pop() {
+                // Don't hold the lock while talking to the server
+                drop(connections);
+                if !self.config.test_on_checkout || self.manager.is_healthy(&connection) {
+                    return Ok(PooledConnection::new(self, connection));
+                }
+                drop(connection);
+                connections = self.connections();
+                connections.open -= 1;
+                continue;
+            }
+
+            if connections.open < self.config.max_size {
+                connections.open += 1;
+                drop(connections);
+                return match self.manager.connect() {
+                    Ok(connection) => Ok(PooledConnection::new(self, connection)),
+                    Err(error) => {
+                        self.connections().open -= 1;
+                        self.returned.notify_one();
+                        Err(PoolError::Connect(error))
+                    }
+                };
+            }
+
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(PoolError::Timeout(self.config.checkout_timeout));
+            }
+            connections = self
+                .returned
+                .wait_timeout(connections, deadline - now)
+                .expect("connection pool poisoned")
+                .0;
+        }
+    }
+
+    fn put_back(&self, connection: M::Connection) {
+        self.connections().idle.push(connection);
+        self.returned.notify_one();
+    }
+}
+
+/// A connection checked out of a [`Pool`], returned to it when dropped.
+pub struct PooledConnection<'pool, M: Manager> {
+    pool: &'pool Pool<M>,
+    connection: Option<M::Connection>,
+}
+
+impl<'pool, M: Manager> PooledConnection<'pool, M> {
+    fn new(pool: &'pool Pool<M>, connection: M::Connection) -> Self {
+        Self {
+            pool,
+            connection: Some(connection),
+        }
+    }
+}
+
+impl<M: Manager> Deref for PooledConnection<'_, M> {
+    type Target = M::Connection;
+
+    fn deref(&self) -> &Self::Target {
+        self.connection
+            .as_ref()
+            .expect("connection already returned")
+    }
+}
+
+impl<M: Manager> DerefMut for PooledConnection<'_, M> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.connection
+            .as_mut()
+            .expect("connection already returned")
+    }
+}
+
+impl<M: Manager> Drop for PooledConnection<'_, M> {
+    fn drop(&mut self) {
+        if let Some(connection) = self.connection.take() {
+            self.pool.put_back(connection);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::di::MySqlConfig;
+    use crate::executor::Executor;
+    use crate::server::Server;
+    use std::sync::Arc;
+    use std::thread;
+
+    struct TestManager {
+        server: Server,
+    }
+
+    impl Manager for TestManager {
+        type Connection = crate::server::Connection;
+
+        fn connect(&self) -> anyhow::Result<Self::Connection> {
+            Ok(self.server.connect()?)
+        }
+
+        fn is_healthy(&self, connection: &Self::Connection) -> bool {
+            connection.ping().is_ok()
+        }
+    }
+
+    fn pool(max_size: usize) -> (Server, Pool<TestManager>) {
+        let server = Server::default();
+        let manager = TestManager {
+            server: server.clone(),
+        };
+        let config = PoolConfig::new()
+            .with_max_size(max_size)
+            .with_checkout_timeout(Duration::from_millis(50));
+        (server, Pool::new(manager, config))
+    }
+
+    #[test]
+    fn test_connections_are_reused() {
+        let (server, pool) = pool(2);
+
+        let connection = pool.get().unwrap();
+        connection
+            .execute("CREATE TABLE pets (name TEXT)", &[])
+            .unwrap();
+        drop(connection);
+
+        let connection = pool.get().unwrap();
+        assert!(connection.query("SELECT * FROM pets", &[]).is_ok());
+        assert_eq!(pool.state(), PoolState { open: 1, idle: 0 });
+        assert_eq!(server.open_connections(), 1);
+
+        drop(connection);
+        assert_eq!(pool.state(), PoolState { open: 1, idle: 1 });
+    }
+
+    #[test]
+    fn test_checkout_times_out() {
+        let (_server, pool) = pool(1);
+
+        let _connection = pool.get().unwrap();
+        let start = Instant::now();
+        assert!(matches!(pool.get(), Err(PoolError::Timeout(_))));
+        assert!(start.elapsed() >= Duration::from_millis(50));
+    }
+
+    #[test]
+    fn test_waiting_checkout_gets_returned_connection() {
+        let (server, pool) = pool(1);
+        let pool = Arc::new(pool);
+
+        let connection = pool.get().unwrap();
+        let waiting = thread::spawn({
+            let pool = pool.clone();
+            move || pool.get().map(|_| ()).is_ok()
+        });
+        thread::sleep(Duration::from_millis(10));
+        drop(connection);
+
+        assert!(waiting.join().unwrap());
+        assert_eq!(server.open_connections(), 1);
+    }
+
+    #[test]
+    fn test_unhealthy_connections_are_replaced() {
+        let (server, pool) = pool(2);
+
+        drop(pool.get().unwrap());
+        server.restart();
+
+        let connection = pool.get().unwrap();
+        assert!(connection.ping().is_ok());
+        assert_eq!(pool.state(), PoolState { open: 1, idle: 0 });
+        assert_eq!(server.open_connections(), 1);
+    }
+
+    #[test]
+    fn test_server_connection_limit() {
+        let (server, pool) = pool(3);
+        server.set_max_connections(Some(1));
+
+        let _connection = pool.get().unwrap();
+        assert!(matches!(pool.get(), Err(PoolError::Connect(_))));
+        // The failed attempt doesn't count towards the pool's size
+        assert_eq!(pool.state(), PoolState { open: 1, idle: 0 });
+    }
+
+    #[test]
+    fn test_mysql_pool() {
+        let config = MySqlConfig::new("test_mysql_pool", 3306, "daniel", "hunter2");
+        let pool = Pool::new(config, PoolConfig::new().with_max_size(1));
+
+        let mysql = pool.get().unwrap();
+        mysql.execute("CREATE TABLE pets (name TEXT)", &[]).unwrap();
+        drop(mysql);
+
+        Server::at("test_mysql_pool:3306").restart();
+        let mysql = pool.get().unwrap();
+        assert!(mysql.ping().is_ok());
+    }
+}
@@ -0,0 +1,132 @@
+use std::sync::Arc;
+
+use crate::error::DatabaseError;
+use crate::value::{FromValue, Value};
+
+/// A single row returned from a query, with its values addressable by column
+/// name or position.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Row {
+    columns: Arc<[String]>,
+    values: Vec<Value>,
+}
+
+impl Row {
+    pub(crate) fn new(columns: Arc<[String]>, values: Vec<Value>) -> Self {
+        debug_assert_eq!(columns.len(), values.len());
+        Self { columns, values }
+    }
+
+    pub fn columns(&self) -> &[String] {
+        &self.columns
+    }
+
+    pub fn values(&self) -> &[Value] {
+        &self.values
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn value<C: AsRef<str>>(&self, column: C) -> Result<&Value, DatabaseError> {
+        let column = column.as_ref();
+        self.columns
+            .iter()
+            .position(|name| name.eq_ignore_ascii_case(column))
+            .map(|index| &self.values[index])
+            .ok_or_else(|| DatabaseError::NoSuchColumn(column.to_string()))
+    }
+
+    /// Reads the named column as `T`, failing if the column is missing or
+    /// holds a value of the wrong type.
+    pub fn get<T, C>(&self, column: C) -> Result<T, DatabaseError>
+    where
+        T: FromValue,
+        C: AsRef<str>,
+    {
+        T::from_value(self.value(column)?)
+    }
+
+    /// Reads the column at `index` as `T`.
+    pub fn get_index<T: FromValue>(&self, index: usize) -> Result<T, DatabaseError> {
+        let value = self
+            .values
+            .get(index)
+            .ok_or_else(|| DatabaseError::NoSuchColumn(index.to_string()))?;
+        T::from_value(value)
+    }
+}
+
+/// Types that can be built from a [`Row`], such as the domain types a store
+/// hands back to its callers.
+pub trait FromRow: Sized {
+    fn from_row(row: &Row) -> anyhow::Result<Self>;
+}
+
+impl FromRow for Row {
+    fn from_row(row: &Row) -> anyhow::Result<Self> {
+        Ok(row.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pet_row() -> Row {
+        Row::new(
+            Arc::from(["carer".to_string(), "name".to_string(), "age".to_string()]),
+            vec![Value::from("Daniel"), Value::from("Yuki"), Value::Null],
+        )
+    }
+
+    #[test]
+    fn test_get_by_name() {
+        let row = pet_row();
+        assert_eq!(row.get::<String, _>("name").unwrap(), "Yuki");
+        assert_eq!(row.get::<String, _>("CARER").unwrap(), "Daniel");
+        assert_eq!(row.get::<Option<i64>, _>("age").unwrap(), None);
+    }
+
+    #[test]
+    fn test_get_by_index() {
+        let row = pet_row();
+        assert_eq!(row.get_index::<String>(1).unwrap(), "Yuki");
+        assert!(row.get_index::<String>(3).is_err());
+    }
+
+    #[test]
+    fn test_missing_column() {
+        let row = pet_row();
+        assert_eq!(
+            row.get::<String, _>("species"),
+            Err(DatabaseError::NoSuchColumn("species".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_from_row() {
+        struct Pet {
+            carer: String,
+            name: String,
+        }
+
+        impl FromRow for Pet {
+            fn from_row(row: &Row) -> anyhow::Result<Self> {
+                Ok(Self {
+                    carer: row.get("carer")?,
+                    name: row.get("name")?,
+                })
+            }
+        }
+
+        let pet = Pet::from_row(&pet_row()).unwrap();
+        assert_eq!(pet.carer, "Daniel");
+        assert_eq!(pet.name, "Yuki");
+    }
+}
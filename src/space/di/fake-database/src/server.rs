@@ -0,0 +1,214 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
+
+use crate::engine::{Database, Outcome};
+use crate::error::DatabaseError;
+use crate::executor::Executor;
+use crate::row::Row;
+use crate::value::Value;
+
+#[derive(Debug, Default)]
+struct Limits {
+    max_connections: Option<usize>,
+    open_connections: usize,
+    // Bumped on every restart so connections opened before it know they're dead
+    generation: u64,
+}
+
+/// A fake database server. It holds the data every connection sees and keeps
+/// track of how many connections are open, so tests can simulate a server
+/// that runs out of connections or drops them all on a restart.
+#[derive(Clone, Debug, Default)]
+pub struct Server {
+    database: Arc<Mutex<Database>>,
+    limits: Arc<Mutex<Limits>>,
+}
+
+impl Server {
+    /// The server listening at `address`. Every connection to the same address
+    /// sees the same data, just like a real server would.
+    pub fn at<A: AsRef<str>>(address: A) -> Self {
+        static SERVERS: OnceLock<Mutex<HashMap<String, Server>>> = OnceLock::new();
+
+        SERVERS
+            .get_or_init(Default::default)
+            .lock()
+            .expect("fake server registry poisoned")
+            .entry(address.as_ref().to_string())
+            .or_default()
+            .clone()
+    }
+
+    fn limits(&self) -> MutexGuard<'_, Limits> {
+        self.limits.lock().expect("fake server limits poisoned")
+    }
+
+    /// Limits how many connections may be open at once, or lifts the limit
+    /// with `None`. Connections that are already open are left alone.
+    pub fn set_max_connections(&self, max_connections: Option<usize>) {
+        self.limits().max_connections = max_connections;
+    }
+
+    pub fn open_connections(&self) -> usize {
+        self.limits().open_connections
+    }
+
+    /// Drops every open connection, as if the server had been restarted. The
+    /// data survives, this is a very forgiving server.
+    pub fn restart(&self) {
+        let mut limits = self.limits();
+        limits.generation += 1;
+        limits.open_connections = 0;
+    }
+
+    pub(crate) fn connect(&self) -> Result<Connection, DatabaseError> {
+        let mut limits = self.limits();
+        if let Some(limit) = limits.max_connections
+            && limits.open_connections >= limit
+        {
+            return Err(DatabaseError::TooManyConnections { limit });
+        }
+        limits.open_connections += 1;
+
+        Ok(Connection {
+            server: self.clone(),
+            generation: limits.generation,
+        })
+    }
+}
+
+/// A single open connection to a [`Server`], closed when dropped.
+#[derive(Debug)]
+pub(crate) struct Connection {
+    server: Server,
+    generation: u64,
+}
+
+impl Connection {
+    pub(crate) fn ping(&self) -> Result<(), DatabaseError> {
+        match self.server.limits().generation == self.generation {
+            true => Ok(()),
+            false => Err(DatabaseError::ConnectionLost),
+        }
+    }
+
+    fn database(&self) -> Result<MutexGuard<'_, Database>, DatabaseError> {
+        self.ping()?;
+        Ok(self.server.database.lock().expect("fake database poisoned"))
+    }
+
+    fn run(&self, sql: &str, parameters: &[Value]) -> anyhow::Result<Outcome> {
+        Ok(self.database()?.execute(sql, parameters)?)
+    }
+
+    /// Takes a private copy of the database for a transaction to work on.
+    pub(crate) fn snapshot(&self) -> Result<Database, DatabaseError> {
+        Ok(self.database()?.clone())
+    }
+
+    /// Replaces the database with a transaction's copy, as long as nothing
+    /// else has changed it since the copy was taken.
+    pub(crate) fn commit(
+        &self,
+        snapshot: Database,
+        base_version: u64,
+    ) -> Result<(), DatabaseError> {
+        let mut database = self.database()?;
+        if database.version() != base_version {
+            return Err(DatabaseError::TransactionConflict);
+        }
+        *database = snapshot;
+        Ok(())
+    }
+}
+
+impl Executor for Connection {
+    fn query<Q>(&self, query: Q, parameters: &[Value]) -> anyhow::Result<Vec<Row>>
+    where
+        Q: AsRef<str>,
+    {
+        Ok(self.run(query.as_ref(), parameters)?.rows)
+    }
+
+    fn execute<Q>(&self, query: Q, parameters: &[Value]) -> anyhow::Result<usize>
+    where
+        Q: AsRef<str>,
+    {
+        Ok(self.run(query.as_ref(), parameters)?.affected)
+    }
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        let mut limits = self.server.limits();
+        // Connections lost in a restart have already been forgotten about
+        if limits.generation == self.generation {
+            limits.open_connections -= 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_connections_to_the_same_address_share_data() {
+        let address = "test_connections_to_the_same_address_share_data:3306";
+        let first = Server::at(address).connect().unwrap();
+        let second = Server::at(address).connect().unwrap();
+
+        first.execute("CREATE TABLE pets (name TEXT)", &[]).unwrap();
+        first
+            .execute("INSERT INTO pets (name) VALUES (?)", &["Yuki".into()])
+            .unwrap();
+
+        assert_eq!(second.query("SELECT * FROM pets", &[]).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_max_connections() {
+        let server = Server::default();
+        server.set_max_connections(Some(2));
+
+        let first = server.connect().unwrap();
+        let _second = server.connect().unwrap();
+        assert_eq!(server.open_connections(), 2);
+        assert_eq!(
+            server.connect().unwrap_err(),
+            DatabaseError::TooManyConnections { limit: 2 }
+        );
+
+        drop(first);
+        assert_eq!(server.open_connections(), 1);
+        assert!(server.connect().is_ok());
+
+        server.set_max_connections(None);
+        let _many: Vec<_> = (0..10).map(|_| server.connect().unwrap()).collect();
+    }
+
+    #[test]
+    fn test_restart_drops_connections() {
+        let server = Server::default();
+        let connection = server.connect().unwrap();
+        connection
+            .execute("CREATE TABLE pets (name TEXT)", &[])
+            .unwrap();
+        assert!(connection.ping().is_ok());
+
+        server.restart();
+        assert_eq!(server.open_connections(), 0);
+        assert_eq!(connection.ping(), Err(DatabaseError::ConnectionLost));
+        let error = connection.query("SELECT * FROM pets", &[]).unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<DatabaseError>(),
+            Some(&DatabaseError::ConnectionLost)
+        );
+
+        drop(connection);
+        assert_eq!(server.open_connections(), 0);
+
+        let connection = server.connect().unwrap();
+        assert!(connection.query("SELECT * FROM pets", &[]).is_ok());
+    }
+}
@@ -0,0 +1,1030 @@
+//! Just enough SQL to run the examples: `CREATE TABLE`, `ALTER TABLE`,
+//! `DROP TABLE`, `INSERT`, `SELECT`, `UPDATE` and `DELETE`, with `WHERE`
+//! clauses made of `column = value` comparisons joined by `AND`.
+
+use crate::error::DatabaseError;
+use crate::value::Value;
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Word(String),
+    Text(String),
+    Number(i64),
+    Placeholder,
+    Comma,
+    OpenParen,
+    CloseParen,
+    Star,
+    Equals,
+    Minus,
+    Semicolon,
+}
+
+fn tokenize(sql: &str) -> Result<Vec<Token>, DatabaseError> {
+    let mut tokens = Vec::new();
+    let mut chars = sql.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        let token = match c {
+            c if c.is_whitespace() => {
+                chars.next();
+                continue;
+            }
+            '-' if sql_comment_follows(&chars) => {
+                chars.by_ref().take_while(|&c| c != '\n').for_each(drop);
+                continue;
+            }
+            '?' => Token::Placeholder,
+            ',' => Token::Comma,
+            '(' => Token::OpenParen,
+            ')' => Token::CloseParen,
+            '*' => Token::Star,
+            '=' => Token::Equals,
+            '-' => Token::Minus,
+            ';' => Token::Semicolon,
+            '\'' => {
+                chars.next();
+                let mut text = String::new();
+                loop {
+                    match chars.next() {
+                        // A doubled quote is an escaped quote
+                        Some('\'') if chars.peek() == Some(&'\'') => {
+                            chars.next();
+                            text.push('\'');
+                        }
+                        Some('\'') => break,
+                        Some(c) => text.push(c),
+                        None => {
+                            return Err(DatabaseError::Syntax(
+                                "unterminated string literal".to_string(),
+                            ));
+                        }
+                    }
+                }
+                tokens.push(Token::Text(text));
+                continue;
+            }
+            c if c.is_ascii_digit() => {
+                let mut digits = String::new();
+                while let Some(&c) = chars.peek().filter(|c| c.is_ascii_digit()) {
+                    digits.push(c);
+                    chars.next();
+                }
+                let number = digits
+                    .parse()
+                    .map_err(|_| DatabaseError::Syntax(format!("number {digits} is too large")))?;
+                tokens.push(Token::Number(number));
+                continue;
+            }
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let mut word = String::new();
+                while let Some(&c) = chars.peek() {
+                    if !(c.is_ascii_alphanumeric() || c == '_') {
+                        break;
+                    }
+                    word.push(c);
+                    chars.next();
+                }
+                tokens.push(Token::Word(word));
+                continue;
+            }
+            c => return Err(DatabaseError::Syntax(format!("unexpected character '{c}'"))),
+        };
+        chars.next();
+        tokens.push(token);
+    }
+
+    Ok(tokens)
+}
+
+fn sql_comment_follows(chars: &std::iter::Peekable<std::str::Chars<'_>>) -> bool {
+    let mut lookahead = chars.clone();
+    lookahead.next() == Some('-') && lookahead.next() == Some('-')
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(crate) enum ColumnType {
+    Int,
+    Text,
+    Bytes,
+    Bool,
+}
+
+impl ColumnType {
+    /// Checks `value` fits in a column of this type, converting it where MySql
+    /// would (eg, `1` into `true` for a boolean column).
+    pub(crate) fn coerce(self, value: Value) -> Result<Value, DatabaseError> {
+        match (self, value) {
+            (_, Value::Null) => Ok(Value::Null),
+            (Self::Int, value @ Value::Int(_)) => Ok(value),
+            (Self::Text, value @ Value::Text(_)) => Ok(value),
+            (Self::Bytes, value @ Value::Bytes(_)) => Ok(value),
+            (Self::Bool, value @ Value::Bool(_)) => Ok(value),
+            (Self::Bool, Value::Int(int @ (0 | 1))) => Ok(Value::Bool(int == 1)),
+            (column_type, value) => Err(DatabaseError::TypeMismatch {
+                expected: column_type.name(),
+                found: value.type_name(),
+            }),
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Int => "INT",
+            Self::Text => "TEXT",
+            Self::Bytes => "BYTES",
+            Self::Bool => "BOOL",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct ColumnDefinition {
+    pub(crate) name: String,
+    pub(crate) column_type: ColumnType,
+    pub(crate) nullable: bool,
+    pub(crate) unique: bool,
+    pub(crate) primary_key: bool,
+    pub(crate) auto_increment: bool,
+    pub(crate) default: Option<Value>,
+    pub(crate) references: Option<ForeignKey>,
+}
+
+/// The column in another table whose values a column's values must match.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct ForeignKey {
+    pub(crate) table: String,
+    pub(crate) column: String,
+}
+
+/// Either a `?` placeholder, identified by its position in the query, or a
+/// literal written straight into the SQL.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Expression {
+    Parameter(usize),
+    Literal(Value),
+}
+
+impl Expression {
+    pub(crate) fn resolve(&self, parameters: &[Value]) -> Value {
+        match self {
+            Self::Parameter(index) => parameters[*index].clone(),
+            Self::Literal(value) => value.clone(),
+        }
+    }
+}
+
+/// The value `UPDATE ... SET` gives a column.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum NewValue {
+    Expression(Expression),
+    /// `LOWER(column)`, another column of the same row in lower case.
+    Lower(String),
+}
+
+/// What an `ALTER TABLE` changes.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Alteration {
+    AddColumn(ColumnDefinition),
+    DropColumn(String),
+    /// A `UNIQUE` constraint over one or more existing columns.
+    AddUnique(Vec<String>),
+    AddForeignKey {
+        column: String,
+        references: ForeignKey,
+    },
+}
+
+/// A `WHERE` clause; every comparison must hold for a row to match.
+pub(crate) type Filter = Vec<(String, Expression)>;
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Columns {
+    All,
+    Named(Vec<String>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Statement {
+    CreateTable {
+        table: String,
+        if_not_exists: bool,
+        columns: Vec<ColumnDefinition>,
+        /// `UNIQUE` and `PRIMARY KEY` constraints spanning several columns
+        unique_keys: Vec<Vec<String>>,
+    },
+    AlterTable {
+        table: String,
+        alteration: Alteration,
+    },
+    DropTable {
+        table: String,
+        if_exists: bool,
+    },
+    Insert {
+        table: String,
+        columns: Vec<String>,
+        rows: Vec<Vec<Expression>>,
+    },
+    Select {
+        table: String,
+        columns: Columns,
+        filter: Filter,
+    },
+    Update {
+        table: String,
+        assignments: Vec<(String, NewValue)>,
+        filter: Filter,
+    },
+    Delete {
+        table: String,
+        filter: Filter,
+    },
+}
+
+/// A parsed statement along with the number of `?` placeholders it expects
+/// to be bound.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Query {
+    pub(crate) statement: Statement,
+    pub(crate) parameters: usize,
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+    parameters: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn next_if(&mut self, expected: &Token) -> bool {
+        let matches = self.peek() == Some(expected);
+        if matches {
+            self.position += 1;
+        }
+        matches
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), DatabaseError> {
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            other => Err(DatabaseError::Syntax(format!(
+                "expected {expected:?}, found {other:?}"
+            ))),
+        }
+    }
+
+    fn keyword(&mut self, keyword: &str) -> Result<(), DatabaseError> {
+        match self.next() {
+            Some(Token::Word(word)) if word.eq_ignore_ascii_case(keyword) => Ok(()),
+            other => Err(DatabaseError::Syntax(format!(
+                "expected {keyword}, found {other:?}"
+            ))),
+        }
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Word(word)) if word.eq_ignore_ascii_case(keyword))
+    }
+
+    fn next_if_keyword(&mut self, keyword: &str) -> bool {
+        let matches = self.is_keyword(keyword);
+        if matches {
+            self.position += 1;
+        }
+        matches
+    }
+
+    fn identifier(&mut self) -> Result<String, DatabaseError> {
+        match self.next() {
+            Some(Token::Word(word)) => Ok(word.to_lowercase()),
+            other => Err(DatabaseError::Syntax(format!(
+                "expected an identifier, found {other:?}"
+            ))),
+        }
+    }
+
+    fn list<T, F>(&mut self, mut item: F) -> Result<Vec<T>, DatabaseError>
+    where
+        F: FnMut(&mut Self) -> Result<T, DatabaseError>,
+    {
+        let mut items = vec![item(self)?];
+        while self.next_if(&Token::Comma) {
+            items.push(item(self)?);
+        }
+        Ok(items)
+    }
+
+    fn parenthesised<T, F>(&mut self, item: F) -> Result<Vec<T>, DatabaseError>
+    where
+        F: FnMut(&mut Self) -> Result<T, DatabaseError>,
+    {
+        self.expect(Token::OpenParen)?;
+        let items = self.list(item)?;
+        self.expect(Token::CloseParen)?;
+        Ok(items)
+    }
+
+    fn literal(&mut self) -> Result<Value, DatabaseError> {
+        match self.next() {
+            Some(Token::Text(text)) => Ok(Value::Text(text)),
+            Some(Token::Number(number)) => Ok(Value::Int(number)),
+            Some(Token::Minus) => match self.next() {
+                Some(Token::Number(number)) => Ok(Value::Int(-number)),
+                other => Err(DatabaseError::Syntax(format!(
+                    "expected a number, found {other:?}"
+                ))),
+            },
+            Some(Token::Word(word)) if word.eq_ignore_ascii_case("NULL") => Ok(Value::Null),
+            Some(Token::Word(word)) if word.eq_ignore_ascii_case("TRUE") => Ok(Value::Bool(true)),
+            Some(Token::Word(word)) if word.eq_ignore_ascii_case("FALSE") => Ok(Value::Bool(false)),
+            other => Err(DatabaseError::Syntax(format!(
+                "expected a value, found {other:?}"
+            ))),
+        }
+    }
+
+    fn expression(&mut self) -> Result<Expression, DatabaseError> {
+        if self.next_if(&Token::Placeholder) {
+            self.parameters += 1;
+            Ok(Expression::Parameter(self.parameters - 1))
+        } else {
+            Ok(Expression::Literal(self.literal()?))
+        }
+    }
+
+    fn assignment(&mut self) -> Result<(String, Expression), DatabaseError> {
+        let column = self.identifier()?;
+        self.expect(Token::Equals)?;
+        Ok((column, self.expression()?))
+    }
+
+    fn new_value(&mut self) -> Result<(String, NewValue), DatabaseError> {
+        let column = self.identifier()?;
+        self.expect(Token::Equals)?;
+        if !self.next_if_keyword("LOWER") {
+            return Ok((column, NewValue::Expression(self.expression()?)));
+        }
+        self.expect(Token::OpenParen)?;
+        let source = self.identifier()?;
+        self.expect(Token::CloseParen)?;
+        Ok((column, NewValue::Lower(source)))
+    }
+
+    fn filter(&mut self) -> Result<Filter, DatabaseError> {
+        let mut filter = Vec::new();
+        if self.next_if_keyword("WHERE") {
+            filter.push(self.assignment()?);
+            while self.next_if_keyword("AND") {
+                filter.push(self.assignment()?);
+            }
+        }
+        Ok(filter)
+    }
+
+    fn finish(&mut self) -> Result<(), DatabaseError> {
+        self.next_if(&Token::Semicolon);
+        match self.peek() {
+            None => Ok(()),
+            Some(token) => Err(DatabaseError::Syntax(format!(
+                "unexpected {token:?} after end of statement"
+            ))),
+        }
+    }
+
+    fn column_type(&mut self) -> Result<ColumnType, DatabaseError> {
+        let name = self.identifier()?;
+        let column_type = match name.as_str() {
+            "int" | "integer" | "bigint" | "smallint" | "tinyint" => ColumnType::Int,
+            "text" | "varchar" | "char" => ColumnType::Text,
+            "blob" | "bytes" | "varbinary" => ColumnType::Bytes,
+            "bool" | "boolean" => ColumnType::Bool,
+            other => {
+                return Err(DatabaseError::Syntax(format!(
+                    "unsupported column type {other}"
+                )));
+            }
+        };
+        // Lengths like VARCHAR(255) are accepted but not enforced
+        if self.next_if(&Token::OpenParen) {
+            match self.next() {
+                Some(Token::Number(_)) => {}
+                other => {
+                    return Err(DatabaseError::Syntax(format!(
+                        "expected a length, found {other:?}"
+                    )));
+                }
+            }
+            self.expect(Token::CloseParen)?;
+        }
+        Ok(column_type)
+    }
+
+    fn column_definition(&mut self) -> Result<ColumnDefinition, DatabaseError> {
+        let mut column = ColumnDefinition {
+            name: self.identifier()?,
+            column_type: self.column_type()?,
+            nullable: true,
+            unique: false,
+            primary_key: false,
+            auto_increment: false,
+            default: None,
+            references: None,
+        };
+
+        loop {
+            if self.next_if_keyword("NOT") {
+                self.keyword("NULL")?;
+                column.nullable = false;
+            } else if self.next_if_keyword("NULL") {
+                column.nullable = true;
+            } else if self.next_if_keyword("PRIMARY") {
+                self.keyword("KEY")?;
+                column.primary_key = true;
+                column.nullable = false;
+            } else if self.next_if_keyword("UNIQUE") {
+                column.unique = true;
+            } else if self.next_if_keyword("AUTO_INCREMENT") {
+                column.auto_increment = true;
+            } else if self.next_if_keyword("DEFAULT") {
+                column.default = Some(self.literal()?);
+            } else if self.is_keyword("REFERENCES") {
+                column.references = Some(self.references()?);
+            } else {
+                break;
+            }
+        }
+
+        if column.auto_increment && column.column_type != ColumnType::Int {
+            return Err(DatabaseError::Syntax(format!(
+                "AUTO_INCREMENT column {} must be an INT",
+                column.name
+            )));
+        }
+
+        Ok(column)
+    }
+
+    fn references(&mut self) -> Result<ForeignKey, DatabaseError> {
+        self.keyword("REFERENCES")?;
+        let table = self.identifier()?;
+        self.expect(Token::OpenParen)?;
+        let column = self.identifier()?;
+        self.expect(Token::CloseParen)?;
+        Ok(ForeignKey { table, column })
+    }
+
+    fn create_table(&mut self) -> Result<Statement, DatabaseError> {
+        self.keyword("CREATE")?;
+        self.keyword("TABLE")?;
+        let if_not_exists = self.next_if_keyword("IF");
+        if if_not_exists {
+            self.keyword("NOT")?;
+            self.keyword("EXISTS")?;
+        }
+        let table = self.identifier()?;
+
+        let mut columns = Vec::new();
+        let mut constraints = Vec::new();
+        let mut foreign_keys = Vec::new();
+        self.expect(Token::OpenParen)?;
+        loop {
+            if self.next_if_keyword("UNIQUE") {
+                constraints.push((false, self.parenthesised(Self::identifier)?));
+            } else if self.next_if_keyword("PRIMARY") {
+                self.keyword("KEY")?;
+                constraints.push((true, self.parenthesised(Self::identifier)?));
+            } else if self.next_if_keyword("FOREIGN") {
+                self.keyword("KEY")?;
+                self.expect(Token::OpenParen)?;
+                let column = self.identifier()?;
+                self.expect(Token::CloseParen)?;
+                foreign_keys.push((column, self.references()?));
+            } else {
+                columns.push(self.column_definition()?);
+            }
+            if !self.next_if(&Token::Comma) {
+                break;
+            }
+        }
+        self.expect(Token::CloseParen)?;
+
+        let find_column = |columns: &mut Vec<ColumnDefinition>, name: &String| {
+            columns
+                .iter_mut()
+                .position(|column| &column.name == name)
+                .ok_or_else(|| DatabaseError::NoSuchColumn(name.clone()))
+        };
+
+        for (column, references) in foreign_keys {
+            let index = find_column(&mut columns, &column)?;
+            columns[index].references = Some(references);
+        }
+
+        let mut primary_keys = columns.iter().filter(|column| column.primary_key).count();
+        let mut unique_keys = Vec::new();
+        for (primary_key, names) in constraints {
+            for name in &names {
+                let index = find_column(&mut columns, name)?;
+                if primary_key {
+                    columns[index].nullable = false;
+                }
+            }
+            primary_keys += usize::from(primary_key);
+
+            match names.as_slice() {
+                [name] if primary_key => {
+                    let index = find_column(&mut columns, name)?;
+                    columns[index].primary_key = true;
+                }
+                [name] => {
+                    let index = find_column(&mut columns, name)?;
+                    columns[index].unique = true;
+                }
+                _ => unique_keys.push(names),
+            }
+        }
+
+        if primary_keys > 1 {
+            return Err(DatabaseError::Syntax(format!(
+                "table {table} has more than one primary key"
+            )));
+        }
+
+        Ok(Statement::CreateTable {
+            table,
+            if_not_exists,
+            columns,
+            unique_keys,
+        })
+    }
+
+    fn alter_table(&mut self) -> Result<Statement, DatabaseError> {
+        self.keyword("ALTER")?;
+        self.keyword("TABLE")?;
+        let table = self.identifier()?;
+        let alteration = if self.next_if_keyword("DROP") {
+            self.keyword("COLUMN")?;
+            Alteration::DropColumn(self.identifier()?)
+        } else {
+            self.keyword("ADD")?;
+            if self.next_if_keyword("UNIQUE") {
+                Alteration::AddUnique(self.parenthesised(Self::identifier)?)
+            } else if self.next_if_keyword("FOREIGN") {
+                self.keyword("KEY")?;
+                self.expect(Token::OpenParen)?;
+                let column = self.identifier()?;
+                self.expect(Token::CloseParen)?;
+                Alteration::AddForeignKey {
+                    column,
+                    references: self.references()?,
+                }
+            } else {
+                self.keyword("COLUMN")?;
+                let column = self.column_definition()?;
+                if column.primary_key || column.auto_increment {
+                    return Err(DatabaseError::Syntax(format!(
+                        "can't add {} as a PRIMARY KEY or AUTO_INCREMENT column",
+                        column.name
+                    )));
+                }
+                Alteration::AddColumn(column)
+            }
+        };
+        Ok(Statement::AlterTable { table, alteration })
+    }
+
+    fn drop_table(&mut self) -> Result<Statement, DatabaseError> {
+        self.keyword("DROP")?;
+        self.keyword("TABLE")?;
+        let if_exists = self.next_if_keyword("IF");
+        if if_exists {
+            self.keyword("EXISTS")?;
+        }
+        Ok(Statement::DropTable {
+            table: self.identifier()?,
+            if_exists,
+        })
+    }
+
+    fn insert(&mut self) -> Result<Statement, DatabaseError> {
+        self.keyword("INSERT")?;
+        self.keyword("INTO")?;
+        let table = self.identifier()?;
+        let columns = self.parenthesised(Self::identifier)?;
+        self.keyword("VALUES")?;
+        let rows = self.list(|parser| parser.parenthesised(Self::expression))?;
+
+        if let Some(row) = rows.iter().find(|row| row.len() != columns.len()) {
+            return Err(DatabaseError::ColumnCount {
+                expected: columns.len(),
+                found: row.len(),
+            });
+        }
+
+        Ok(Statement::Insert {
+            table,
+            columns,
+            rows,
+        })
+    }
+
+    fn select(&mut self) -> Result<Statement, DatabaseError> {
+        self.keyword("SELECT")?;
+        let columns = if self.next_if(&Token::Star) {
+            Columns::All
+        } else {
+            Columns::Named(self.list(Self::identifier)?)
+        };
+        self.keyword("FROM")?;
+        Ok(Statement::Select {
+            table: self.identifier()?,
+            columns,
+            filter: self.filter()?,
+        })
+    }
+
+    fn update(&mut self) -> Result<Statement, DatabaseError> {
+        self.keyword("UPDATE")?;
+        let table = self.identifier()?;
+        self.keyword("SET")?;
+        Ok(Statement::Update {
+            table,
+            assignments: self.list(Self::new_value)?,
+            filter: self.filter()?,
+        })
+    }
+
+    fn delete(&mut self) -> Result<Statement, DatabaseError> {
+        self.keyword("DELETE")?;
+        self.keyword("FROM")?;
+        Ok(Statement::Delete {
+            table: self.identifier()?,
+            filter: self.filter()?,
+        })
+    }
+}
+
+pub(crate) fn parse(sql: &str) -> Result<Query, DatabaseError> {
+    let mut parser = Parser {
+        tokens: tokenize(sql)?,
+        position: 0,
+        parameters: 0,
+    };
+
+    let statement = match parser.peek() {
+        Some(Token::Word(word)) => match word.to_uppercase().as_str() {
+            "CREATE" => parser.create_table()?,
+            "ALTER" => parser.alter_table()?,
+            "DROP" => parser.drop_table()?,
+            "INSERT" => parser.insert()?,
+            "SELECT" => parser.select()?,
+            "UPDATE" => parser.update()?,
+            "DELETE" => parser.delete()?,
+            other => {
+                return Err(DatabaseError::Syntax(format!(
+                    "unsupported statement {other}"
+                )));
+            }
+        },
+        other => {
+            return Err(DatabaseError::Syntax(format!(
+                "expected a statement, found {other:?}"
+            )));
+        }
+    };
+
+    parser.finish()?;
+    Ok(Query {
+        statement,
+        parameters: parser.parameters,
+    })
+}
+
+/// Splits a script into its statements at each `;`, skipping any that are
+/// empty or only comments.
+pub(crate) fn split(script: &str) -> Result<Vec<&str>, DatabaseError> {
+    let mut statements = Vec::new();
+    let mut start = 0;
+    let mut chars = script.char_indices().peekable();
+
+    while let Some((index, c)) = chars.next() {
+        match c {
+            // Skips to the closing quote. An escaped quote closes the string and
+            // opens another straight away, which works out the same
+            '\'' if !chars.any(|(_, c)| c == '\'') => {
+                return Err(DatabaseError::Syntax(
+                    "unterminated string literal".to_string(),
+                ));
+            }
+            '-' if matches!(chars.peek(), Some((_, '-'))) => {
+                chars.find(|&(_, c)| c == '\n');
+            }
+            ';' => {
+                statements.push(&script[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    statements.push(&script[start..]);
+
+    let mut non_empty = Vec::new();
+    for statement in statements {
+        if !tokenize(statement)?.is_empty() {
+            non_empty.push(statement.trim());
+        }
+    }
+    Ok(non_empty)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_create_table() {
+        let query = parse(
+            "
+                CREATE TABLE IF NOT EXISTS users (
+                  id INT PRIMARY KEY AUTO_INCREMENT,
+                  email_address VARCHAR(255) NOT NULL UNIQUE,
+                  username TEXT NOT NULL,
+                  verified BOOL DEFAULT FALSE,
+                  UNIQUE (username)
+                )
+            ",
+        )
+        .unwrap();
+
+        let Statement::CreateTable {
+            table,
+            if_not_exists,
+            columns,
+            unique_keys,
+        } = query.statement
+        else {
+            panic!("expected CREATE TABLE, found {query:?}");
+        };
+
+        assert_eq!(table, "users");
+        assert!(unique_keys.is_empty());
+        assert!(if_not_exists);
+        assert_eq!(columns.len(), 4);
+        assert!(columns[0].primary_key && columns[0].auto_increment && !columns[0].nullable);
+        assert_eq!(columns[1].column_type, ColumnType::Text);
+        assert!(columns[1].unique && !columns[1].nullable);
+        assert!(columns[2].unique);
+        assert_eq!(columns[3].default, Some(Value::Bool(false)));
+    }
+
+    #[test]
+    fn test_parse_foreign_and_composite_keys() {
+        let query = parse(
+            "
+                CREATE TABLE pets (
+                  carer VARCHAR(255) NOT NULL REFERENCES users (username),
+                  vet VARCHAR(255),
+                  name VARCHAR(255) NOT NULL,
+                  PRIMARY KEY (carer, name),
+                  FOREIGN KEY (vet) REFERENCES vets (name)
+                )
+            ",
+        )
+        .unwrap();
+
+        let Statement::CreateTable {
+            columns,
+            unique_keys,
+            ..
+        } = query.statement
+        else {
+            panic!("expected CREATE TABLE, found {query:?}");
+        };
+
+        assert_eq!(
+            columns[0].references,
+            Some(ForeignKey {
+                table: "users".to_string(),
+                column: "username".to_string(),
+            })
+        );
+        assert_eq!(
+            columns[1].references,
+            Some(ForeignKey {
+                table: "vets".to_string(),
+                column: "name".to_string(),
+            })
+        );
+        assert!(!columns[2].primary_key && !columns[2].nullable);
+        assert_eq!(
+            unique_keys,
+            vec![vec!["carer".to_string(), "name".to_string()]]
+        );
+    }
+
+    #[test]
+    fn test_parse_insert() {
+        let query = parse(
+            "
+                INSERT INTO users
+                  (email_address, username)
+                VALUES
+                  (?, ?),
+                  ('yuki@example.com', 'Yuki')
+            ",
+        )
+        .unwrap();
+
+        assert_eq!(query.parameters, 2);
+        assert_eq!(
+            query.statement,
+            Statement::Insert {
+                table: "users".to_string(),
+                columns: vec!["email_address".to_string(), "username".to_string()],
+                rows: vec![
+                    vec![Expression::Parameter(0), Expression::Parameter(1)],
+                    vec![
+                        Expression::Literal(Value::from("yuki@example.com")),
+                        Expression::Literal(Value::from("Yuki")),
+                    ],
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_select() {
+        let query =
+            parse("select * from Users where EMAIL_ADDRESS = ? and verified = true;").unwrap();
+
+        assert_eq!(query.parameters, 1);
+        assert_eq!(
+            query.statement,
+            Statement::Select {
+                table: "users".to_string(),
+                columns: Columns::All,
+                filter: vec![
+                    ("email_address".to_string(), Expression::Parameter(0)),
+                    (
+                        "verified".to_string(),
+                        Expression::Literal(Value::Bool(true))
+                    ),
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_update_and_delete() {
+        let query = parse("UPDATE pets SET carer = ?, age = -1 WHERE name = ?").unwrap();
+        assert_eq!(query.parameters, 2);
+        assert_eq!(
+            query.statement,
+            Statement::Update {
+                table: "pets".to_string(),
+                assignments: vec![
+                    (
+                        "carer".to_string(),
+                        NewValue::Expression(Expression::Parameter(0))
+                    ),
+                    (
+                        "age".to_string(),
+                        NewValue::Expression(Expression::Literal(Value::Int(-1)))
+                    ),
+                ],
+                filter: vec![("name".to_string(), Expression::Parameter(1))],
+            }
+        );
+
+        let query = parse("UPDATE users SET username_key = LOWER(username)").unwrap();
+        assert_eq!(
+            query.statement,
+            Statement::Update {
+                table: "users".to_string(),
+                assignments: vec![(
+                    "username_key".to_string(),
+                    NewValue::Lower("username".to_string())
+                )],
+                filter: vec![],
+            }
+        );
+
+        let query = parse("DELETE FROM pets -- everything!\n").unwrap();
+        assert_eq!(
+            query.statement,
+            Statement::Delete {
+                table: "pets".to_string(),
+                filter: vec![],
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_alter_table() {
+        let alteration = |sql| match parse(sql).unwrap().statement {
+            Statement::AlterTable { table, alteration } => {
+                assert_eq!(table, "users");
+                alteration
+            }
+            statement => panic!("expected ALTER TABLE, found {statement:?}"),
+        };
+
+        let Alteration::AddColumn(column) = alteration(
+            "ALTER TABLE users ADD COLUMN username_key VARCHAR(255) NOT NULL DEFAULT ''",
+        ) else {
+            panic!("expected ADD COLUMN");
+        };
+        assert_eq!(column.name, "username_key");
+        assert!(!column.nullable);
+        assert_eq!(column.default, Some(Value::from("")));
+
+        assert_eq!(
+            alteration("ALTER TABLE users ADD UNIQUE (username_key)"),
+            Alteration::AddUnique(vec!["username_key".to_string()])
+        );
+        assert_eq!(
+            alteration("ALTER TABLE users ADD FOREIGN KEY (vet) REFERENCES vets (name)"),
+            Alteration::AddForeignKey {
+                column: "vet".to_string(),
+                references: ForeignKey {
+                    table: "vets".to_string(),
+                    column: "name".to_string(),
+                },
+            }
+        );
+        assert_eq!(
+            alteration("ALTER TABLE users DROP COLUMN username_key"),
+            Alteration::DropColumn("username_key".to_string())
+        );
+        assert!(matches!(
+            parse("ALTER TABLE users ADD COLUMN id INT PRIMARY KEY"),
+            Err(DatabaseError::Syntax(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_string_escapes() {
+        let query = parse("SELECT * FROM pets WHERE name = 'Yuki''s toy'").unwrap();
+        let Statement::Select { filter, .. } = query.statement else {
+            panic!("expected SELECT");
+        };
+        assert_eq!(filter[0].1, Expression::Literal(Value::from("Yuki's toy")));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(matches!(
+            parse("TRUNCATE users"),
+            Err(DatabaseError::Syntax(_))
+        ));
+        assert!(matches!(
+            parse("SELECT * FROM users WHERE name = 'Yuki"),
+            Err(DatabaseError::Syntax(_))
+        ));
+        assert!(matches!(
+            parse("CREATE TABLE pets (name FLOAT)"),
+            Err(DatabaseError::Syntax(_))
+        ));
+        assert!(matches!(
+            parse("CREATE TABLE pets (name TEXT, UNIQUE (carer))"),
+            Err(DatabaseError::NoSuchColumn(_))
+        ));
+        assert_eq!(
+            parse("INSERT INTO pets (carer, name) VALUES (?)"),
+            Err(DatabaseError::ColumnCount {
+                expected: 2,
+                found: 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_split() {
+        let script = "
+            -- Comments; don't end statements
+            CREATE TABLE pets (name TEXT);
+            INSERT INTO pets (name) VALUES ('Yuki; the cat'), ('Ted''s dog');;
+            -- Nothing else to see here
+        ";
+
+        assert_eq!(
+            split(script).unwrap(),
+            vec![
+                "-- Comments; don't end statements\n            CREATE TABLE pets (name TEXT)",
+                "INSERT INTO pets (name) VALUES ('Yuki; the cat'), ('Ted''s dog')",
+            ]
+        );
+        assert!(split("INSERT INTO pets (name) VALUES ('Yuki);").is_err());
+    }
+}
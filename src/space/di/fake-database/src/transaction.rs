@@ -0,0 +1,226 @@
+use std::cell::RefCell;
+
+use crate::engine::Database;
+use crate::executor::Executor;
+use crate::row::Row;
+use crate::server::Connection;
+use crate::value::Value;
+
+/// A set of changes that either all happen or don't happen at all.
+///
+/// Our fake server keeps this very simple: the transaction works on its own
+/// copy of the database, which replaces the real one on commit. If anything
+/// else changed the database in the meantime the commit fails rather than
+/// losing that change. Dropping a transaction without committing it throws
+/// its copy away, rolling it back.
+#[derive(Debug)]
+pub struct Transaction<'c> {
+    connection: &'c Connection,
+    snapshot: RefCell<Database>,
+    base_version: u64,
+}
+
+impl<'c> Transaction<'c> {
+    pub(crate) fn begin(connection: &'c Connection) -> anyhow::Result<Self> {
+        let snapshot = connection.snapshot()?;
+        Ok(Self {
+            connection,
+            base_version: snapshot.version(),
+            snapshot: RefCell::new(snapshot),
+        })
+    }
+
+    /// Runs `f` inside a new transaction, committing if it succeeds and
+    /// rolling back if it returns an error.
+    pub(crate) fn run<T, F>(connection: &'c Connection, f: F) -> anyhow::Result<T>
+    where
+        F: FnOnce(&Transaction<'c>) -> anyhow::Result<T>,
+    {
+        let transaction = Self::begin(connection)?;
+        match f(&transaction) {
+            Ok(value) => {
+                transaction.commit()?;
+                Ok(value)
+            }
+            Err(error) => {
+                transaction.rollback()?;
+                Err(error)
+            }
+        }
+    }
+
+    pub fn commit(self) -> anyhow::Result<()> {
+        Ok(self
+            .connection
+            .commit(self.snapshot.into_inner(), self.base_version)?)
+    }
+
+    pub fn rollback(self) -> anyhow::Result<()> {
+        // Throwing away our copy of the database is all it takes
+        Ok(())
+    }
+}
+
+impl Executor for Transaction<'_> {
+    fn query<Q>(&self, query: Q, parameters: &[Value]) -> anyhow::Result<Vec<Row>>
+    where
+        Q: AsRef<str>,
+    {
+        self.connection.ping()?;
+        Ok(self
+            .snapshot
+            .borrow_mut()
+            .execute(query.as_ref(), parameters)?
+            .rows)
+    }
+
+    fn execute<Q>(&self, query: Q, parameters: &[Value]) -> anyhow::Result<usize>
+    where
+        Q: AsRef<str>,
+    {
+        self.connection.ping()?;
+        Ok(self
+            .snapshot
+            .borrow_mut()
+            .execute(query.as_ref(), parameters)?
+            .affected)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::DatabaseError;
+    use crate::server::Server;
+
+    fn pets() -> Connection {
+        let connection = Server::default().connect().unwrap();
+        connection
+            .execute("CREATE TABLE users (username TEXT PRIMARY KEY)", &[])
+            .unwrap();
+        connection
+            .execute(
+                "CREATE TABLE pets (carer TEXT NOT NULL, name TEXT NOT NULL)",
+                &[],
+            )
+            .unwrap();
+        connection
+    }
+
+    fn count<E: Executor>(executor: &E, table: &str) -> usize {
+        executor
+            .query(format!("SELECT * FROM {table}"), &[])
+            .unwrap()
+            .len()
+    }
+
+    #[test]
+    fn test_commit() {
+        let connection = pets();
+
+        let transaction = Transaction::begin(&connection).unwrap();
+        transaction
+            .execute("INSERT INTO users (username) VALUES ('Daniel')", &[])
+            .unwrap();
+        transaction
+            .execute(
+                "INSERT INTO pets (carer, name) VALUES ('Daniel', 'Yuki')",
+                &[],
+            )
+            .unwrap();
+
+        // Nobody else can see the changes until they're committed
+        assert_eq!(count(&transaction, "users"), 1);
+        assert_eq!(count(&connection, "users"), 0);
+
+        transaction.commit().unwrap();
+        assert_eq!(count(&connection, "users"), 1);
+        assert_eq!(count(&connection, "pets"), 1);
+    }
+
+    #[test]
+    fn test_rollback() {
+        let connection = pets();
+
+        let transaction = Transaction::begin(&connection).unwrap();
+        transaction
+            .execute("INSERT INTO users (username) VALUES ('Daniel')", &[])
+            .unwrap();
+        transaction.rollback().unwrap();
+
+        assert_eq!(count(&connection, "users"), 0);
+    }
+
+    #[test]
+    fn test_rollback_on_drop() {
+        let connection = pets();
+
+        {
+            let transaction = Transaction::begin(&connection).unwrap();
+            transaction
+                .execute("INSERT INTO users (username) VALUES ('Daniel')", &[])
+                .unwrap();
+        }
+
+        assert_eq!(count(&connection, "users"), 0);
+    }
+
+    #[test]
+    fn test_run_commits_on_success() {
+        let connection = pets();
+
+        let stored = Transaction::run(&connection, |transaction| {
+            transaction.execute("INSERT INTO users (username) VALUES ('Daniel')", &[])?;
+            transaction.execute(
+                "INSERT INTO pets (carer, name) VALUES ('Daniel', 'Yuki')",
+                &[],
+            )
+        })
+        .unwrap();
+
+        assert_eq!(stored, 1);
+        assert_eq!(count(&connection, "users"), 1);
+        assert_eq!(count(&connection, "pets"), 1);
+    }
+
+    #[test]
+    fn test_run_rolls_back_on_error() {
+        let connection = pets();
+
+        let error = Transaction::run(&connection, |transaction| {
+            transaction.execute("INSERT INTO users (username) VALUES ('Daniel')", &[])?;
+            // The pet has no name so the insert fails, taking the user with it
+            transaction.execute("INSERT INTO pets (carer) VALUES ('Daniel')", &[])
+        })
+        .unwrap_err();
+
+        assert!(matches!(
+            error.downcast_ref::<DatabaseError>(),
+            Some(DatabaseError::NotNull { .. })
+        ));
+        assert_eq!(count(&connection, "users"), 0);
+        assert_eq!(count(&connection, "pets"), 0);
+    }
+
+    #[test]
+    fn test_conflicting_commit_fails() {
+        let connection = pets();
+
+        let transaction = Transaction::begin(&connection).unwrap();
+        transaction
+            .execute("INSERT INTO users (username) VALUES ('Daniel')", &[])
+            .unwrap();
+
+        // Someone else sneaks a change in before we commit
+        connection
+            .execute("INSERT INTO users (username) VALUES ('Yuki')", &[])
+            .unwrap();
+
+        let error = transaction.commit().unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<DatabaseError>(),
+            Some(&DatabaseError::TransactionConflict)
+        );
+        assert_eq!(count(&connection, "users"), 1);
+    }
+}
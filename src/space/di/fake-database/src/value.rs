@@ -0,0 +1,243 @@
+use std::fmt;
+
+use crate::error::DatabaseError;
+
+/// A single typed value that can be bound to a query parameter or read out of
+/// a column.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Value {
+    Null,
+    Int(i64),
+    Text(String),
+    Bytes(Vec<u8>),
+    Bool(bool),
+}
+
+impl Value {
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Self::Null => "NULL",
+            Self::Int(_) => "INT",
+            Self::Text(_) => "TEXT",
+            Self::Bytes(_) => "BYTES",
+            Self::Bool(_) => "BOOL",
+        }
+    }
+
+    pub fn is_null(&self) -> bool {
+        matches!(self, Self::Null)
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Null => write!(f, "NULL"),
+            Self::Int(value) => write!(f, "{value}"),
+            Self::Text(value) => write!(f, "'{value}'"),
+            Self::Bytes(value) => write!(f, "<{} bytes>", value.len()),
+            Self::Bool(value) => write!(f, "{value}"),
+        }
+    }
+}
+
+impl From<i64> for Value {
+    fn from(value: i64) -> Self {
+        Self::Int(value)
+    }
+}
+
+impl From<i32> for Value {
+    fn from(value: i32) -> Self {
+        Self::Int(value.into())
+    }
+}
+
+impl From<u32> for Value {
+    fn from(value: u32) -> Self {
+        Self::Int(value.into())
+    }
+}
+
+impl From<u16> for Value {
+    fn from(value: u16) -> Self {
+        Self::Int(value.into())
+    }
+}
+
+impl From<u8> for Value {
+    fn from(value: u8) -> Self {
+        Self::Int(value.into())
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Self::Text(value.to_string())
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Self::Text(value)
+    }
+}
+
+impl From<&String> for Value {
+    fn from(value: &String) -> Self {
+        Self::Text(value.clone())
+    }
+}
+
+impl From<&[u8]> for Value {
+    fn from(value: &[u8]) -> Self {
+        Self::Bytes(value.to_vec())
+    }
+}
+
+impl From<Vec<u8>> for Value {
+    fn from(value: Vec<u8>) -> Self {
+        Self::Bytes(value)
+    }
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Self::Null, Into::into)
+    }
+}
+
+/// Types that can be read out of a single [`Value`].
+pub trait FromValue: Sized {
+    fn from_value(value: &Value) -> Result<Self, DatabaseError>;
+}
+
+fn type_mismatch(expected: &'static str, found: &Value) -> DatabaseError {
+    DatabaseError::TypeMismatch {
+        expected,
+        found: found.type_name(),
+    }
+}
+
+impl FromValue for Value {
+    fn from_value(value: &Value) -> Result<Self, DatabaseError> {
+        Ok(value.clone())
+    }
+}
+
+impl FromValue for i64 {
+    fn from_value(value: &Value) -> Result<Self, DatabaseError> {
+        match value {
+            Value::Int(int) => Ok(*int),
+            other => Err(type_mismatch("INT", other)),
+        }
+    }
+}
+
+macro_rules! from_value_for_int {
+    ($($int:ty),*) => {
+        $(
+            impl FromValue for $int {
+                fn from_value(value: &Value) -> Result<Self, DatabaseError> {
+                    let int = i64::from_value(value)?;
+                    <$int>::try_from(int).map_err(|_| DatabaseError::OutOfRange {
+                        value: int,
+                        target: stringify!($int),
+                    })
+                }
+            }
+        )*
+    };
+}
+
+from_value_for_int!(i32, u32, u16, u8);
+
+impl FromValue for String {
+    fn from_value(value: &Value) -> Result<Self, DatabaseError> {
+        match value {
+            Value::Text(text) => Ok(text.clone()),
+            other => Err(type_mismatch("TEXT", other)),
+        }
+    }
+}
+
+impl FromValue for Vec<u8> {
+    fn from_value(value: &Value) -> Result<Self, DatabaseError> {
+        match value {
+            Value::Bytes(bytes) => Ok(bytes.clone()),
+            other => Err(type_mismatch("BYTES", other)),
+        }
+    }
+}
+
+impl FromValue for bool {
+    fn from_value(value: &Value) -> Result<Self, DatabaseError> {
+        match value {
+            Value::Bool(boolean) => Ok(*boolean),
+            // MySql doesn't really have booleans, they're just tiny ints
+            Value::Int(0) => Ok(false),
+            Value::Int(1) => Ok(true),
+            other => Err(type_mismatch("BOOL", other)),
+        }
+    }
+}
+
+impl<T: FromValue> FromValue for Option<T> {
+    fn from_value(value: &Value) -> Result<Self, DatabaseError> {
+        match value {
+            Value::Null => Ok(None),
+            other => T::from_value(other).map(Some),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_into_value() {
+        assert_eq!(Value::from(42i64), Value::Int(42));
+        assert_eq!(Value::from(7u8), Value::Int(7));
+        assert_eq!(Value::from("Yuki"), Value::Text("Yuki".to_string()));
+        assert_eq!(Value::from(vec![1u8, 2]), Value::Bytes(vec![1, 2]));
+        assert_eq!(Value::from(true), Value::Bool(true));
+        assert_eq!(Value::from(None::<i64>), Value::Null);
+        assert_eq!(Value::from(Some("Yuki")), Value::Text("Yuki".to_string()));
+    }
+
+    #[test]
+    fn test_from_value() {
+        assert_eq!(i64::from_value(&Value::Int(42)).unwrap(), 42);
+        assert_eq!(u8::from_value(&Value::Int(42)).unwrap(), 42);
+        assert_eq!(String::from_value(&Value::from("Yuki")).unwrap(), "Yuki");
+        assert!(bool::from_value(&Value::Int(1)).unwrap());
+        assert_eq!(Option::<i64>::from_value(&Value::Null).unwrap(), None);
+        assert_eq!(Option::<i64>::from_value(&Value::Int(3)).unwrap(), Some(3));
+    }
+
+    #[test]
+    fn test_from_value_errors() {
+        assert!(matches!(
+            i64::from_value(&Value::from("Yuki")),
+            Err(DatabaseError::TypeMismatch {
+                expected: "INT",
+                found: "TEXT"
+            })
+        ));
+        assert!(matches!(
+            u8::from_value(&Value::Int(256)),
+            Err(DatabaseError::OutOfRange {
+                value: 256,
+                target: "u8"
+            })
+        ));
+        assert!(String::from_value(&Value::Null).is_err());
+    }
+}
@@ -0,0 +1,209 @@
+use newtypes::*;
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, HashMap};
+use std::time::{Duration, Instant};
+
+use crate::time_source::{RealTime, TimeSource};
+use crate::user_store::{User, UserStore};
+
+/// How well a [`CachedUserStore`] is doing.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    /// Users dropped to make room for others.
+    pub evictions: u64,
+}
+
+struct Entry {
+    user: User,
+    expires: Instant,
+    last_used: u64,
+}
+
+/// The cached users, by [`Username::key`] so lookups ignore case like the
+/// stores do, with an index from email address to that key so both lookups
+/// share one copy of each user.
+#[derive(Default)]
+struct Cache {
+    users: HashMap<String, Entry>,
+    emails: HashMap<String, String>,
+    // Usernames by when they were last used, least recent first
+    recency: BTreeMap<u64, String>,
+    tick: u64,
+}
+
+impl Cache {
+    fn remove(&mut self, username: &str) {
+        if let Some(entry) = self.users.remove(username) {
+            self.emails.remove(entry.user.email_address.as_str());
+            self.recency.remove(&entry.last_used);
+        }
+    }
+
+    /// The user with `username` if they're cached and haven't expired.
+    fn get(&mut self, username: &str, now: Instant) -> Option<User> {
+        let entry = self.users.get(username)?;
+        if entry.expires <= now {
+            self.remove(username);
+            return None;
+        }
+
+        self.tick += 1;
+        let entry = self.users.get_mut(username)?;
+        self.recency.remove(&entry.last_used);
+        entry.last_used = self.tick;
+        self.recency.insert(self.tick, username.to_string());
+        Some(entry.user.clone())
+    }
+
+    /// Caches `user`, returning whether someone had to be evicted to fit
+    /// them in.
+    fn insert(&mut self, user: User, expires: Instant, capacity: usize) -> bool {
+        let username = user.username.key();
+        self.remove(&username);
+
+        let evicted = self.users.len() >= capacity;
+        if evicted && let Some((_, oldest)) = self.recency.pop_first() {
+            self.remove(&oldest);
+        }
+
+        self.tick += 1;
+        self.emails
+            .insert(user.email_address.as_str().to_string(), username.clone());
+        self.recency.insert(self.tick, username.clone());
+        self.users.insert(
+            username,
+            Entry {
+                user,
+                expires,
+                last_used: self.tick,
+            },
+        );
+        evicted
+    }
+}
+
+/// Fronts a [`UserStore`] with a cache of the users looked up recently, like
+/// Redis in front of a SQL database.
+///
+/// Users are cached by both email address and username, for `ttl` at most,
+/// and the least recently used are evicted once there are `capacity` of
+/// them. Storing a user invalidates anything cached under their email
+/// address or username. Only users that were found are cached.
+pub struct CachedUserStore<U: UserStore, C: TimeSource = RealTime> {
+    inner: U,
+    time: C,
+    capacity: usize,
+    ttl: Duration,
+    cache: RefCell<Cache>,
+    stats: Cell<CacheStats>,
+}
+
+impl<U: UserStore> CachedUserStore<U> {
+    pub fn new(inner: U) -> Self {
+        Self {
+            inner,
+            time: RealTime,
+            capacity: 1024,
+            ttl: Duration::from_secs(60),
+            cache: RefCell::default(),
+            stats: Cell::default(),
+        }
+    }
+}
+
+impl<U: UserStore, C: TimeSource> CachedUserStore<U, C> {
+    /// The most users kept at once.
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity.max(1);
+        self
+    }
+
+    /// How long a user is cached before they're looked up again.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    pub fn with_time_source<D: TimeSource>(self, time: D) -> CachedUserStore<U, D> {
+        CachedUserStore {
+            inner: self.inner,
+            time,
+            capacity: self.capacity,
+            ttl: self.ttl,
+            cache: self.cache,
+            stats: self.stats,
+        }
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.stats.get()
+    }
+
+    /// How many users are cached right now, including any that have expired
+    /// but haven't been asked for since.
+    pub fn len(&self) -> usize {
+        self.cache.borrow().users.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn record(&self, update: impl FnOnce(&mut CacheStats)) {
+        let mut stats = self.stats.get();
+        update(&mut stats);
+        self.stats.set(stats);
+    }
+
+    /// Returns the cached user for `username`, or looks them up with `fetch`
+    /// and caches them.
+    fn read_through<F>(&self, username: Option<String>, fetch: F) -> anyhow::Result<User>
+    where
+        F: FnOnce() -> anyhow::Result<User>,
+    {
+        let now = self.time.now();
+        let cached = username.and_then(|username| self.cache.borrow_mut().get(&username, now));
+        if let Some(user) = cached {
+            self.record(|stats| stats.hits += 1);
+            return Ok(user);
+        }
+
+        self.record(|stats| stats.misses += 1);
+        let user = fetch()?;
+        let evicted = self
+            .cache
+            .borrow_mut()
+            .insert(user.clone(), now + self.ttl, self.capacity);
+        if evicted {
+            self.record(|stats| stats.evictions += 1);
+        }
+        Ok(user)
+    }
+}
+
+impl<U: UserStore, C: TimeSource> UserStore for CachedUserStore<U, C> {
+    fn store(&self, user: &User) -> anyhow::Result<()> {
+        {
+            let mut cache = self.cache.borrow_mut();
+            let by_email = cache.emails.get(user.email_address.as_str()).cloned();
+            if let Some(username) = by_email {
+                cache.remove(&username);
+            }
+            cache.remove(&user.username.key());
+        }
+        self.inner.store(user)
+    }
+
+    fn get_by_email(&self, email: &EmailAddress) -> anyhow::Result<User> {
+        let username = self.cache.borrow().emails.get(email.as_str()).cloned();
+        self.read_through(username, || self.inner.get_by_email(email))
+    }
+
+    fn get_by_username(&self, username: &Username) -> anyhow::Result<User> {
+        self.read_through(Some(username.key()), || {
+            self.inner.get_by_username(username)
+        })
+    }
+}
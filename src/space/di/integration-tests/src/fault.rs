@@ -0,0 +1,130 @@
+use newtypes::*;
+use std::cell::Cell;
+use std::fmt;
+use std::thread;
+use std::time::Duration;
+
+use crate::rng::Rng;
+use crate::user_store::{User, UserStore};
+
+/// The error a [`FaultyUserStore`] returns in place of a real one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InjectedFault {
+    pub method: &'static str,
+    /// Which call to the store failed, counting from 1.
+    pub call: usize,
+}
+
+impl fmt::Display for InjectedFault {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Injected fault in {} (call {})", self.method, self.call)
+    }
+}
+
+impl std::error::Error for InjectedFault {}
+
+/// Wraps a [`UserStore`] and makes it misbehave on purpose, so we can check
+/// the code using it copes. With no faults configured it just passes every
+/// call through.
+pub struct FaultyUserStore<U: UserStore> {
+    inner: U,
+    every_nth: Option<usize>,
+    failure_rate: Option<(f64, Rng)>,
+    latency: Duration,
+    partial_store: bool,
+    calls: Cell<usize>,
+}
+
+impl<U: UserStore> FaultyUserStore<U> {
+    pub fn new(inner: U) -> Self {
+        Self {
+            inner,
+            every_nth: None,
+            failure_rate: None,
+            latency: Duration::ZERO,
+            partial_store: false,
+            calls: Cell::new(0),
+        }
+    }
+
+    /// Fails every `n`th call, counting calls to every method together.
+    pub fn with_every_nth_call_failing(mut self, n: usize) -> Self {
+        self.every_nth = Some(n.max(1));
+        self
+    }
+
+    /// Fails each call with probability `rate`, from `0.0` (never) to `1.0`
+    /// (always). The same `seed` fails the same calls every run.
+    pub fn with_failure_rate(mut self, rate: f64, seed: u64) -> Self {
+        self.failure_rate = Some((rate.clamp(0.0, 1.0), Rng::new(seed)));
+        self
+    }
+
+    /// Waits `latency` before every call, failed or not.
+    pub fn with_latency(mut self, latency: Duration) -> Self {
+        self.latency = latency;
+        self
+    }
+
+    /// When a call to `store` fails, stores the user anyway before failing,
+    /// like a write that succeeds but whose response never arrives.
+    pub fn with_partial_store_failure(mut self, partial_store: bool) -> Self {
+        self.partial_store = partial_store;
+        self
+    }
+
+    /// How many calls the store has had, failed or not.
+    pub fn calls(&self) -> usize {
+        self.calls.get()
+    }
+
+    pub fn into_inner(self) -> U {
+        self.inner
+    }
+
+    /// Counts the call and decides whether it fails.
+    fn fault(&self, method: &'static str) -> Option<InjectedFault> {
+        if !self.latency.is_zero() {
+            thread::sleep(self.latency);
+        }
+
+        let call = self.calls.get() + 1;
+        self.calls.set(call);
+
+        let nth = self.every_nth.is_some_and(|n| call.is_multiple_of(n));
+        // Always roll, so whether one call fails doesn't depend on the others
+        let random = self
+            .failure_rate
+            .as_ref()
+            .is_some_and(|(rate, rng)| rng.next_f64() < *rate);
+        (nth || random).then_some(InjectedFault { method, call })
+    }
+}
+
+impl<U: UserStore> UserStore for FaultyUserStore<U> {
+    fn store(&self, user: &User) -> anyhow::Result<()> {
+        match self.fault("store") {
+            Some(fault) => {
+                if self.partial_store {
+                    self.inner.store(user)?;
+                }
+                Err(fault.into())
+            }
+            None => self.inner.store(user),
+        }
+    }
+
+    fn get_by_email(&self, email: &EmailAddress) -> anyhow::Result<User> {
+        match self.fault("get_by_email") {
+            Some(fault) => Err(fault.into()),
+            None => self.inner.get_by_email(email),
+        }
+    }
+
+    fn get_by_username(&self, username: &Username) -> anyhow::Result<User> {
+        match self.fault("get_by_username") {
+            Some(fault) => Err(fault.into()),
+            None => self.inner.get_by_username(username),
+        }
+    }
+}
@@ -1,6 +1,13 @@
+pub mod cache;
+pub mod fault;
+pub mod mock;
 pub mod mysql;
+pub mod pet_store;
 pub mod postgres;
 pub mod redis;
+pub mod retry;
+mod rng;
 pub mod stub;
 pub mod surreal_db;
+pub mod time_source;
 pub mod user_store;
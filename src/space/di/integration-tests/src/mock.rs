@@ -0,0 +1,208 @@
+use newtypes::*;
+use std::cell::{Cell, RefCell};
+use std::fmt;
+
+use crate::user_store::{User, UserStore};
+
+/// A call made to a [`MockUserStore`], with its argument.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Call {
+    Store(User),
+    GetByEmail(EmailAddress),
+    GetByUsername(Username),
+}
+
+type Respond<A, R> = Box<dyn Fn(&A) -> anyhow::Result<R>>;
+
+/// What a test expects of one method: optionally the argument it's called
+/// with and how many times, and what to return.
+pub struct Expectation<A, R> {
+    method: &'static str,
+    argument: Option<A>,
+    times: Option<usize>,
+    respond: Respond<A, R>,
+    calls: Cell<usize>,
+}
+
+impl<A: fmt::Debug + PartialEq, R> Expectation<A, R> {
+    fn new(method: &'static str, respond: Respond<A, R>) -> Self {
+        Self {
+            method,
+            argument: None,
+            times: None,
+            respond,
+            calls: Cell::new(0),
+        }
+    }
+
+    /// Only matches calls with this argument.
+    pub fn with(&mut self, argument: A) -> &mut Self {
+        self.argument = Some(argument);
+        self
+    }
+
+    /// Expects exactly `times` matching calls.
+    pub fn times(&mut self, times: usize) -> &mut Self {
+        self.times = Some(times);
+        self
+    }
+
+    pub fn once(&mut self) -> &mut Self {
+        self.times(1)
+    }
+
+    pub fn never(&mut self) -> &mut Self {
+        self.times(0)
+    }
+
+    /// Answers matching calls with `respond`.
+    pub fn returning<F>(&mut self, respond: F) -> &mut Self
+    where
+        F: Fn(&A) -> anyhow::Result<R> + 'static,
+    {
+        self.respond = Box::new(respond);
+        self
+    }
+
+    fn matches(&self, argument: &A) -> bool {
+        self.argument
+            .as_ref()
+            .is_none_or(|expected| expected == argument)
+    }
+
+    fn saturated(&self) -> bool {
+        self.times.is_some_and(|times| self.calls.get() >= times)
+    }
+
+    fn check(&self) -> Result<(), String> {
+        match self.times {
+            Some(times) if times != self.calls.get() => Err(format!(
+                "expected {}({}) to be called {times} time(s) but it was called {}",
+                self.method,
+                self.argument
+                    .as_ref()
+                    .map_or("_".to_string(), |argument| format!("{argument:?}")),
+                self.calls.get()
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Answers a call with the first expectation that matches it and still
+/// wants more calls, or failing that the first that matches at all, which
+/// will then fail verification for being called too often.
+fn respond<A: fmt::Debug + PartialEq, R>(
+    expectations: &[Expectation<A, R>],
+    method: &str,
+    argument: &A,
+) -> anyhow::Result<R> {
+    let expectation = expectations
+        .iter()
+        .find(|expectation| expectation.matches(argument) && !expectation.saturated())
+        .or_else(|| {
+            expectations
+                .iter()
+                .find(|expectation| expectation.matches(argument))
+        })
+        .unwrap_or_else(|| panic!("unexpected call to {method}({argument:?})"));
+    expectation.calls.set(expectation.calls.get() + 1);
+    (expectation.respond)(argument)
+}
+
+/// A [`UserStore`] that does whatever the test tells it to, records every
+/// call made to it and checks its expectations were met when it's dropped.
+///
+/// Any call that doesn't match an expectation panics, so tests spell out
+/// every interaction they're expecting.
+#[derive(Default)]
+pub struct MockUserStore {
+    calls: RefCell<Vec<Call>>,
+    store: Vec<Expectation<User, ()>>,
+    get_by_email: Vec<Expectation<EmailAddress, User>>,
+    get_by_username: Vec<Expectation<Username, User>>,
+}
+
+impl MockUserStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Expects `store` to be called, succeeding unless told otherwise.
+    pub fn expect_store(&mut self) -> &mut Expectation<User, ()> {
+        self.store
+            .push(Expectation::new("store", Box::new(|_| Ok(()))));
+        self.store.last_mut().expect("just pushed")
+    }
+
+    /// Expects `get_by_email` to be called. There's no sensible user to
+    /// return by default so it fails unless told otherwise.
+    pub fn expect_get_by_email(&mut self) -> &mut Expectation<EmailAddress, User> {
+        self.get_by_email.push(Expectation::new(
+            "get_by_email",
+            Box::new(|email| anyhow::bail!("no response set for get_by_email({email})")),
+        ));
+        self.get_by_email.last_mut().expect("just pushed")
+    }
+
+    /// Expects `get_by_username` to be called. There's no sensible user to
+    /// return by default so it fails unless told otherwise.
+    pub fn expect_get_by_username(&mut self) -> &mut Expectation<Username, User> {
+        self.get_by_username.push(Expectation::new(
+            "get_by_username",
+            Box::new(|username| anyhow::bail!("no response set for get_by_username({username})")),
+        ));
+        self.get_by_username.last_mut().expect("just pushed")
+    }
+
+    /// Every call made so far, in order.
+    pub fn calls(&self) -> Vec<Call> {
+        self.calls.borrow().clone()
+    }
+
+    /// Panics if any expectation hasn't been met. Called automatically on
+    /// drop, but calling it directly gives a clearer place for the failure.
+    pub fn verify(&self) {
+        let failures: Vec<_> = self
+            .store
+            .iter()
+            .map(Expectation::check)
+            .chain(self.get_by_email.iter().map(Expectation::check))
+            .chain(self.get_by_username.iter().map(Expectation::check))
+            .filter_map(Result::err)
+            .collect();
+        if !failures.is_empty() {
+            panic!("unmet expectations:\n{}", failures.join("\n"));
+        }
+    }
+}
+
+impl Drop for MockUserStore {
+    fn drop(&mut self) {
+        // A second panic would abort and hide the first
+        if !std::thread::panicking() {
+            self.verify();
+        }
+    }
+}
+
+impl UserStore for MockUserStore {
+    fn store(&self, user: &User) -> anyhow::Result<()> {
+        self.calls.borrow_mut().push(Call::Store(user.clone()));
+        respond(&self.store, "store", user)
+    }
+
+    fn get_by_email(&self, email: &EmailAddress) -> anyhow::Result<User> {
+        self.calls
+            .borrow_mut()
+            .push(Call::GetByEmail(email.clone()));
+        respond(&self.get_by_email, "get_by_email", email)
+    }
+
+    fn get_by_username(&self, username: &Username) -> anyhow::Result<User> {
+        self.calls
+            .borrow_mut()
+            .push(Call::GetByUsername(username.clone()));
+        respond(&self.get_by_username, "get_by_username", username)
+    }
+}
@@ -1,8 +1,11 @@
+use fake_database::di::{DatabaseError, Executor, FromRow, Migrator, Row, Value};
 use newtypes::*;
+use schema::MIGRATIONS;
+use std::sync::Arc;
 
 use crate::{
-    stub::StubUserStore,
-    user_store::{User, UserStore},
+    pet_store::{Pet, PetStore, PetStoreError},
+    user_store::{User, UserStore, UserStoreError},
 };
 
 pub struct MySqlConfig {}
@@ -13,38 +16,213 @@ impl MySqlConfig {
     }
 }
 
-pub struct MySql {}
+/// A connection to a fresh in-memory database with every migration applied,
+/// shared by the stores built from it.
+#[derive(Clone)]
+pub struct MySql {
+    inner: Arc<fake_database::di::MySql>,
+}
 
 impl MySql {
     pub fn connect(config: MySqlConfig) -> anyhow::Result<Self> {
         let _ = config;
-        Ok(Self {})
+        let mysql = Self {
+            inner: Arc::new(fake_database::di::MySql::in_memory()?),
+        };
+        Migrator::new(MIGRATIONS).up(mysql.inner())?;
+        Ok(mysql)
+    }
+
+    /// The connection the stores share, for running migrations against.
+    pub fn inner(&self) -> &fake_database::di::MySql {
+        &self.inner
+    }
+}
+
+impl Executor for MySql {
+    fn query<Q>(&self, query: Q, parameters: &[Value]) -> anyhow::Result<Vec<Row>>
+    where
+        Q: AsRef<str>,
+    {
+        self.inner.query(query, parameters)
+    }
+
+    fn execute<Q>(&self, query: Q, parameters: &[Value]) -> anyhow::Result<usize>
+    where
+        Q: AsRef<str>,
+    {
+        self.inner.execute(query, parameters)
+    }
+}
+
+impl FromRow for User {
+    fn from_row(row: &Row) -> anyhow::Result<Self> {
+        Ok(Self {
+            username: row.get::<String, _>("username")?.parse()?,
+            email_address: row.get::<String, _>("email_address")?.parse()?,
+        })
+    }
+}
+
+impl FromRow for Pet {
+    fn from_row(row: &Row) -> anyhow::Result<Self> {
+        Ok(Self {
+            name: row.get("name")?,
+            carer: row.get::<String, _>("carer")?.parse()?,
+        })
     }
 }
 
 pub struct MySqlUserStore {
-    inner: StubUserStore,
+    mysql: MySql,
 }
 
 impl MySqlUserStore {
     pub fn new(mysql: MySql) -> Self {
-        let _ = mysql;
-        Self {
-            inner: StubUserStore::new(),
-        }
+        Self { mysql }
+    }
+
+    fn get_by(&self, column: &str, value: &str) -> anyhow::Result<User> {
+        self.mysql
+            .query_as(
+                format!("SELECT username, email_address FROM users WHERE {column} = ?"),
+                &[value.into()],
+            )?
+            .pop()
+            .ok_or(UserStoreError::UserNotFound.into())
     }
 }
 
 impl UserStore for MySqlUserStore {
     fn store(&self, user: &User) -> anyhow::Result<()> {
-        self.inner.store(user)
+        let result = self.mysql.execute(
+            "INSERT INTO users (username, username_key, email_address) VALUES (?, ?, ?)",
+            &[
+                user.username.as_str().into(),
+                user.username.key().into(),
+                user.email_address.as_str().into(),
+            ],
+        );
+        match result.map_err(|error| error.downcast::<DatabaseError>()) {
+            Ok(_) => Ok(()),
+            Err(Ok(DatabaseError::UniqueViolation { column, .. })) if column == "email_address" => {
+                Err(UserStoreError::EmailAddressExists.into())
+            }
+            Err(Ok(DatabaseError::UniqueViolation { column, .. }))
+                if column == "username" || column == "username_key" =>
+            {
+                Err(UserStoreError::UsernameExists.into())
+            }
+            Err(Ok(error)) => Err(error.into()),
+            Err(Err(error)) => Err(error),
+        }
     }
 
     fn get_by_email(&self, email: &EmailAddress) -> anyhow::Result<User> {
-        self.inner.get_by_email(email)
+        self.get_by("email_address", email.as_str())
     }
 
-    fn get_by_username(&self, username: &Username) -> anyhow::Result<crate::user_store::User> {
-        self.inner.get_by_username(username)
+    fn get_by_username(&self, username: &Username) -> anyhow::Result<User> {
+        self.get_by("username_key", &username.key())
+    }
+}
+
+pub struct MySqlPetStore {
+    mysql: MySql,
+}
+
+impl MySqlPetStore {
+    pub fn new(mysql: MySql) -> Self {
+        Self { mysql }
+    }
+
+    /// The username as `users` has it, which may differ in case from how it
+    /// was asked for. `pets.carer` has to match it exactly.
+    fn stored_username(&self, username: &Username) -> anyhow::Result<Option<Username>> {
+        let users = self.mysql.query(
+            "SELECT username FROM users WHERE username_key = ?",
+            &[username.key().into()],
+        )?;
+        users
+            .first()
+            .map(|user| Ok(user.get::<String, _>("username")?.parse()?))
+            .transpose()
+    }
+
+    /// Turns the database's constraint errors into the ones callers of a
+    /// [`PetStore`] expect.
+    fn pet_store_error(error: anyhow::Error) -> anyhow::Error {
+        match error.downcast::<DatabaseError>() {
+            Ok(DatabaseError::ForeignKeyViolation { .. }) => PetStoreError::CarerNotFound.into(),
+            Ok(DatabaseError::UniqueViolation { .. }) => PetStoreError::PetExists.into(),
+            Ok(error) => error.into(),
+            Err(error) => error,
+        }
+    }
+}
+
+impl PetStore for MySqlPetStore {
+    fn store(&self, pet: &Pet) -> anyhow::Result<()> {
+        // A carer who isn't a user fails the foreign key below
+        let carer = self
+            .stored_username(&pet.carer)?
+            .unwrap_or_else(|| pet.carer.clone());
+        self.mysql
+            .execute(
+                "INSERT INTO pets (carer, carer_key, name) VALUES (?, ?, ?)",
+                &[
+                    carer.as_str().into(),
+                    carer.key().into(),
+                    pet.name.as_str().into(),
+                ],
+            )
+            .map_err(Self::pet_store_error)?;
+        Ok(())
+    }
+
+    fn get_by_carer(&self, carer: &Username) -> anyhow::Result<Vec<Pet>> {
+        if self.stored_username(carer)?.is_none() {
+            return Err(PetStoreError::CarerNotFound.into());
+        }
+        self.mysql.query_as(
+            "SELECT carer, name FROM pets WHERE carer_key = ?",
+            &[carer.key().into()],
+        )
+    }
+
+    fn transfer(&self, pet: &Pet, carer: &Username) -> anyhow::Result<Pet> {
+        let carer = self
+            .stored_username(carer)?
+            .unwrap_or_else(|| carer.clone());
+        let transferred = self
+            .mysql
+            .execute(
+                "UPDATE pets SET carer = ?, carer_key = ? WHERE carer_key = ? AND name = ?",
+                &[
+                    carer.as_str().into(),
+                    carer.key().into(),
+                    pet.carer.key().into(),
+                    pet.name.as_str().into(),
+                ],
+            )
+            .map_err(Self::pet_store_error)?;
+        if transferred == 0 {
+            return Err(PetStoreError::PetNotFound.into());
+        }
+        Ok(Pet {
+            name: pet.name.clone(),
+            carer,
+        })
+    }
+
+    fn delete(&self, pet: &Pet) -> anyhow::Result<()> {
+        let deleted = self.mysql.execute(
+            "DELETE FROM pets WHERE carer_key = ? AND name = ?",
+            &[pet.carer.key().into(), pet.name.as_str().into()],
+        )?;
+        if deleted == 0 {
+            return Err(PetStoreError::PetNotFound.into());
+        }
+        Ok(())
     }
 }
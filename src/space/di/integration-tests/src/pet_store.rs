@@ -0,0 +1,40 @@
+use newtypes::*;
+use std::fmt;
+
+#[derive(Debug)]
+pub enum PetStoreError {
+    CarerNotFound,
+    PetExists,
+    PetNotFound,
+}
+
+impl fmt::Display for PetStoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PetStoreError::CarerNotFound => write!(f, "Carer not found"),
+            PetStoreError::PetExists => write!(f, "Pet exists"),
+            PetStoreError::PetNotFound => write!(f, "Pet not found"),
+        }
+    }
+}
+
+impl std::error::Error for PetStoreError {}
+
+/// A pet, looked after by the user with the username `carer`. A carer can't
+/// look after two pets with the same name.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pet {
+    pub name: String,
+    pub carer: Username,
+}
+
+pub trait PetStore {
+    fn store(&self, pet: &Pet) -> anyhow::Result<()>;
+
+    fn get_by_carer(&self, carer: &Username) -> anyhow::Result<Vec<Pet>>;
+
+    /// Hands `pet` over to `carer`, returning the pet as it is now.
+    fn transfer(&self, pet: &Pet, carer: &Username) -> anyhow::Result<Pet>;
+
+    fn delete(&self, pet: &Pet) -> anyhow::Result<()>;
+}
@@ -0,0 +1,181 @@
+use fake_database::di::{DatabaseError, PoolError};
+use newtypes::*;
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+use std::time::Duration;
+
+use crate::fault::InjectedFault;
+use crate::rng::Rng;
+use crate::time_source::{RealTime, TimeSource};
+use crate::user_store::{User, UserStore};
+
+/// Whether `error` might go away if we try again, like a dropped connection,
+/// rather than being the answer, like a username that's already taken.
+pub fn is_transient(error: &anyhow::Error) -> bool {
+    if error.is::<InjectedFault>() {
+        return true;
+    }
+    if let Some(error) = error.downcast_ref::<DatabaseError>() {
+        return matches!(
+            error,
+            DatabaseError::ConnectionLost
+                | DatabaseError::TooManyConnections { .. }
+                | DatabaseError::TransactionConflict
+        );
+    }
+    error.is::<PoolError>()
+}
+
+/// A failed attempt that's about to be retried, for [`RetryingUserStore`]'s
+/// retry hook.
+#[derive(Debug)]
+pub struct Retry<'a> {
+    pub method: &'static str,
+    /// The attempt that failed, counting from 1.
+    pub attempt: u32,
+    /// How long we'll wait before the next attempt.
+    pub delay: Duration,
+    pub error: &'a anyhow::Error,
+}
+
+type OnRetry = Box<dyn Fn(&Retry<'_>)>;
+
+/// Retries the [`UserStore`] it wraps when it fails with a transient error,
+/// waiting longer after each attempt.
+///
+/// The wait doubles each time from `base_delay` up to `max_delay`, and with
+/// jitter on is a random amount between half and all of that, so clients that
+/// failed together don't all retry together.
+///
+/// `store` is retried too, which isn't always safe: if a failed attempt did
+/// store the user, the retry fails with `UsernameExists`.
+pub struct RetryingUserStore<U: UserStore, C: TimeSource = RealTime> {
+    inner: U,
+    time: C,
+    max_attempts: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+    jitter: Option<Rng>,
+    is_transient: fn(&anyhow::Error) -> bool,
+    on_retry: Option<OnRetry>,
+}
+
+impl<U: UserStore> RetryingUserStore<U> {
+    pub fn new(inner: U) -> Self {
+        Self {
+            inner,
+            time: RealTime,
+            max_attempts: 3,
+            base_delay: Duration::from_millis(50),
+            max_delay: Duration::from_secs(2),
+            jitter: Some(Rng::new(RandomState::new().hash_one(0))),
+            is_transient,
+            on_retry: None,
+        }
+    }
+}
+
+impl<U: UserStore, C: TimeSource> RetryingUserStore<U, C> {
+    /// The most times an operation is tried, including the first.
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// The wait before the first retry, and the most any wait can grow to.
+    pub fn with_delays(mut self, base_delay: Duration, max_delay: Duration) -> Self {
+        self.base_delay = base_delay;
+        self.max_delay = max_delay.max(base_delay);
+        self
+    }
+
+    /// Jitters waits using `seed`, so tests see the same waits every run.
+    pub fn with_jitter_seed(mut self, seed: u64) -> Self {
+        self.jitter = Some(Rng::new(seed));
+        self
+    }
+
+    pub fn without_jitter(mut self) -> Self {
+        self.jitter = None;
+        self
+    }
+
+    /// Decides which errors are worth retrying, in place of [`is_transient`].
+    pub fn with_classifier(mut self, is_transient: fn(&anyhow::Error) -> bool) -> Self {
+        self.is_transient = is_transient;
+        self
+    }
+
+    /// Calls `on_retry` before each wait, eg to log or count retries.
+    pub fn with_on_retry<F: Fn(&Retry<'_>) + 'static>(mut self, on_retry: F) -> Self {
+        self.on_retry = Some(Box::new(on_retry));
+        self
+    }
+
+    pub fn with_time_source<D: TimeSource>(self, time: D) -> RetryingUserStore<U, D> {
+        RetryingUserStore {
+            inner: self.inner,
+            time,
+            max_attempts: self.max_attempts,
+            base_delay: self.base_delay,
+            max_delay: self.max_delay,
+            jitter: self.jitter,
+            is_transient: self.is_transient,
+            on_retry: self.on_retry,
+        }
+    }
+
+    /// How long to wait after the `attempt`th attempt fails.
+    fn delay(&self, attempt: u32) -> Duration {
+        let backoff = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(attempt - 1))
+            .min(self.max_delay);
+        match &self.jitter {
+            Some(rng) => backoff.mul_f64(0.5 + rng.next_f64() / 2.0),
+            None => backoff,
+        }
+    }
+
+    fn retry<T, F>(&self, method: &'static str, operation: F) -> anyhow::Result<T>
+    where
+        F: Fn() -> anyhow::Result<T>,
+    {
+        let mut attempt = 1;
+        loop {
+            let error = match operation() {
+                Ok(value) => return Ok(value),
+                Err(error) => error,
+            };
+            if attempt >= self.max_attempts || !(self.is_transient)(&error) {
+                return Err(error);
+            }
+
+            let delay = self.delay(attempt);
+            if let Some(on_retry) = &self.on_retry {
+                on_retry(&Retry {
+                    method,
+                    attempt,
+                    delay,
+                    error: &error,
+                });
+            }
+            self.time.sleep(delay);
+            attempt += 1;
+        }
+    }
+}
+
+impl<U: UserStore, C: TimeSource> UserStore for RetryingUserStore<U, C> {
+    fn store(&self, user: &User) -> anyhow::Result<()> {
+        self.retry("store", || self.inner.store(user))
+    }
+
+    fn get_by_email(&self, email: &EmailAddress) -> anyhow::Result<User> {
+        self.retry("get_by_email", || self.inner.get_by_email(email))
+    }
+
+    fn get_by_username(&self, username: &Username) -> anyhow::Result<User> {
+        self.retry("get_by_username", || self.inner.get_by_username(username))
+    }
+}
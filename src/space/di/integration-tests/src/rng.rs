@@ -0,0 +1,31 @@
+use std::cell::Cell;
+
+/// SplitMix64, which is tiny and plenty random enough for injecting faults
+/// and jittering retries. The same seed always gives the same numbers.
+#[derive(Debug)]
+pub(crate) struct Rng {
+    state: Cell<u64>,
+}
+
+impl Rng {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self {
+            state: Cell::new(seed),
+        }
+    }
+
+    pub(crate) fn next_u64(&self) -> u64 {
+        let state = self.state.get().wrapping_add(0x9e3779b97f4a7c15);
+        self.state.set(state);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0.0..1.0`.
+    pub(crate) fn next_f64(&self) -> f64 {
+        // The top 53 bits fill an f64's mantissa exactly
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
@@ -1,7 +1,10 @@
 use newtypes::*;
 use std::cell::RefCell;
 
-use crate::user_store::{User, UserStore, UserStoreError};
+use crate::{
+    pet_store::{Pet, PetStore, PetStoreError},
+    user_store::{User, UserStore, UserStoreError},
+};
 
 #[derive(Default)]
 pub struct StubUserStore {
@@ -44,3 +47,75 @@ impl UserStore for StubUserStore {
             .ok_or(UserStoreError::UserNotFound.into())
     }
 }
+
+/// Keeps pets in memory, checking carers exist in `users`.
+pub struct StubPetStore<U: UserStore> {
+    users: U,
+    pets: RefCell<Vec<Pet>>,
+}
+
+impl<U: UserStore> StubPetStore<U> {
+    pub fn new(users: U) -> Self {
+        Self {
+            users,
+            pets: RefCell::default(),
+        }
+    }
+
+    fn check_carer(&self, carer: &Username) -> anyhow::Result<()> {
+        self.users
+            .get_by_username(carer)
+            .map(|_| ())
+            .map_err(|_| PetStoreError::CarerNotFound.into())
+    }
+}
+
+impl<U: UserStore> PetStore for StubPetStore<U> {
+    fn store(&self, pet: &Pet) -> anyhow::Result<()> {
+        self.check_carer(&pet.carer)?;
+        if self.pets.borrow().contains(pet) {
+            return Err(PetStoreError::PetExists.into());
+        }
+        self.pets.borrow_mut().push(pet.clone());
+        Ok(())
+    }
+
+    fn get_by_carer(&self, carer: &Username) -> anyhow::Result<Vec<Pet>> {
+        self.check_carer(carer)?;
+        Ok(self
+            .pets
+            .borrow()
+            .iter()
+            .filter(|pet| &pet.carer == carer)
+            .cloned()
+            .collect())
+    }
+
+    fn transfer(&self, pet: &Pet, carer: &Username) -> anyhow::Result<Pet> {
+        let mut pets = self.pets.borrow_mut();
+        let index = pets
+            .iter()
+            .position(|stored| stored == pet)
+            .ok_or(PetStoreError::PetNotFound)?;
+        self.check_carer(carer)?;
+        let transferred = Pet {
+            name: pet.name.clone(),
+            carer: carer.clone(),
+        };
+        if pet != &transferred && pets.contains(&transferred) {
+            return Err(PetStoreError::PetExists.into());
+        }
+        pets[index] = transferred.clone();
+        Ok(transferred)
+    }
+
+    fn delete(&self, pet: &Pet) -> anyhow::Result<()> {
+        let mut pets = self.pets.borrow_mut();
+        let index = pets
+            .iter()
+            .position(|stored| stored == pet)
+            .ok_or(PetStoreError::PetNotFound)?;
+        pets.remove(index);
+        Ok(())
+    }
+}
@@ -0,0 +1,77 @@
+use std::cell::{Cell, RefCell};
+use std::time::{Duration, Instant};
+
+/// Tells the time, so decorators that care about it can be tested without
+/// waiting around.
+pub trait TimeSource {
+    fn now(&self) -> Instant;
+
+    fn sleep(&self, duration: Duration);
+}
+
+impl<C: TimeSource + ?Sized> TimeSource for &C {
+    fn now(&self) -> Instant {
+        (**self).now()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        (**self).sleep(duration)
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RealTime;
+
+impl TimeSource for RealTime {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        std::thread::sleep(duration)
+    }
+}
+
+/// A time source that only moves when it's told to. Sleeping moves it on
+/// straight away, and is remembered so tests can check how long things
+/// waited.
+#[derive(Debug)]
+pub struct FakeTime {
+    now: Cell<Instant>,
+    sleeps: RefCell<Vec<Duration>>,
+}
+
+impl Default for FakeTime {
+    fn default() -> Self {
+        Self {
+            now: Cell::new(Instant::now()),
+            sleeps: RefCell::default(),
+        }
+    }
+}
+
+impl FakeTime {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn advance(&self, by: Duration) {
+        self.now.set(self.now.get() + by);
+    }
+
+    /// Every sleep so far, in order.
+    pub fn sleeps(&self) -> Vec<Duration> {
+        self.sleeps.borrow().clone()
+    }
+}
+
+impl TimeSource for FakeTime {
+    fn now(&self) -> Instant {
+        self.now.get()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        self.sleeps.borrow_mut().push(duration);
+        self.advance(duration);
+    }
+}
@@ -21,6 +21,7 @@ impl fmt::Display for UserStoreError {
 impl std::error::Error for UserStoreError {}
 
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct User {
     pub username: Username,
     pub email_address: EmailAddress,
@@ -33,3 +34,17 @@ pub trait UserStore {
 
     fn get_by_username(&self, username: &Username) -> anyhow::Result<User>;
 }
+
+impl<U: UserStore + ?Sized> UserStore for &U {
+    fn store(&self, user: &User) -> anyhow::Result<()> {
+        (**self).store(user)
+    }
+
+    fn get_by_email(&self, email: &EmailAddress) -> anyhow::Result<User> {
+        (**self).get_by_email(email)
+    }
+
+    fn get_by_username(&self, username: &Username) -> anyhow::Result<User> {
+        (**self).get_by_username(username)
+    }
+}
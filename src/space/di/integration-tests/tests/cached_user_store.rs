@@ -0,0 +1,171 @@
+use std::str::FromStr;
+use std::time::Duration;
+
+use integration_tests::{
+    cache::{CacheStats, CachedUserStore},
+    mysql::{MySql, MySqlConfig, MySqlUserStore},
+    stub::StubUserStore,
+    time_source::FakeTime,
+    user_store::{User, UserStore, UserStoreError},
+};
+use newtypes::*;
+
+fn user(name: &str) -> User {
+    User {
+        username: Username::from_str(name).unwrap(),
+        email_address: EmailAddress::from_str(&format!("{name}@example.com")).unwrap(),
+    }
+}
+
+fn stats(hits: u64, misses: u64, evictions: u64) -> CacheStats {
+    CacheStats {
+        hits,
+        misses,
+        evictions,
+    }
+}
+
+fn test_cache_hits_by_either_key<U: UserStore>(user_store: U) {
+    let daniel = user("daniel");
+    user_store.store(&daniel).unwrap();
+    let cached = CachedUserStore::new(user_store);
+
+    assert_eq!(cached.get_by_email(&daniel.email_address).unwrap(), daniel);
+    assert_eq!(cached.get_by_username(&daniel.username).unwrap(), daniel);
+    assert_eq!(cached.get_by_email(&daniel.email_address).unwrap(), daniel);
+    assert_eq!(cached.stats(), stats(2, 1, 0));
+
+    // Users that can't be found aren't cached
+    let ted = user("ted");
+    for _ in 0..2 {
+        let error = cached.get_by_username(&ted.username).unwrap_err();
+        assert!(matches!(
+            error.downcast_ref(),
+            Some(UserStoreError::UserNotFound)
+        ));
+    }
+    assert_eq!(cached.stats(), stats(2, 3, 0));
+    assert_eq!(cached.len(), 1);
+}
+
+fn test_cache_expires<U: UserStore>(user_store: U) {
+    let daniel = user("daniel");
+    user_store.store(&daniel).unwrap();
+    let time = FakeTime::new();
+    let cached = CachedUserStore::new(user_store)
+        .with_ttl(Duration::from_secs(10))
+        .with_time_source(&time);
+
+    cached.get_by_username(&daniel.username).unwrap();
+    time.advance(Duration::from_secs(9));
+    cached.get_by_username(&daniel.username).unwrap();
+    assert_eq!(cached.stats(), stats(1, 1, 0));
+
+    time.advance(Duration::from_secs(1));
+    cached.get_by_email(&daniel.email_address).unwrap();
+    assert_eq!(cached.stats(), stats(1, 2, 0));
+}
+
+fn test_cache_evicts_least_recently_used<U: UserStore>(user_store: U) {
+    let [daniel, ted, yuki] = [user("daniel"), user("ted"), user("yuki")];
+    for user in [&daniel, &ted, &yuki] {
+        user_store.store(user).unwrap();
+    }
+    let cached = CachedUserStore::new(user_store).with_capacity(2);
+
+    cached.get_by_username(&daniel.username).unwrap();
+    cached.get_by_username(&ted.username).unwrap();
+    // Daniel is now more recently used than Ted, so Ted makes way for Yuki
+    cached.get_by_email(&daniel.email_address).unwrap();
+    cached.get_by_username(&yuki.username).unwrap();
+    assert_eq!(cached.stats(), stats(1, 3, 1));
+    assert_eq!(cached.len(), 2);
+
+    cached.get_by_username(&daniel.username).unwrap();
+    cached.get_by_username(&ted.username).unwrap();
+    assert_eq!(cached.stats(), stats(2, 4, 2));
+}
+
+fn test_store_invalidates<U: UserStore>(user_store: U) {
+    let daniel = user("daniel");
+    user_store.store(&daniel).unwrap();
+    let cached = CachedUserStore::new(user_store);
+
+    cached.get_by_username(&daniel.username).unwrap();
+    assert_eq!(cached.len(), 1);
+
+    // Even a failed store might have changed the backend, so don't trust
+    // what we had
+    assert!(cached.store(&daniel).is_err());
+    assert!(cached.is_empty());
+
+    cached.get_by_email(&daniel.email_address).unwrap();
+    assert_eq!(cached.stats(), stats(0, 2, 0));
+}
+
+fn test_cache_ignores_username_case<U: UserStore>(user_store: U) {
+    let daniel = user("Daniel");
+    user_store.store(&daniel).unwrap();
+    let cached = CachedUserStore::new(user_store);
+
+    cached.get_by_username(&daniel.username).unwrap();
+    let shouting = Username::from_str("DANIEL").unwrap();
+    assert_eq!(cached.get_by_username(&shouting).unwrap(), daniel);
+    assert_eq!(cached.stats(), stats(1, 1, 0));
+    assert_eq!(cached.len(), 1);
+}
+
+fn mysql_user_store() -> MySqlUserStore {
+    let config = MySqlConfig::from_env().unwrap();
+    MySqlUserStore::new(MySql::connect(config).unwrap())
+}
+
+#[test]
+fn test_mysql_cache_hits_by_either_key() {
+    test_cache_hits_by_either_key(mysql_user_store());
+}
+
+#[test]
+fn test_stub_cache_hits_by_either_key() {
+    test_cache_hits_by_either_key(StubUserStore::new());
+}
+
+#[test]
+fn test_mysql_cache_expires() {
+    test_cache_expires(mysql_user_store());
+}
+
+#[test]
+fn test_stub_cache_expires() {
+    test_cache_expires(StubUserStore::new());
+}
+
+#[test]
+fn test_mysql_cache_evicts_least_recently_used() {
+    test_cache_evicts_least_recently_used(mysql_user_store());
+}
+
+#[test]
+fn test_stub_cache_evicts_least_recently_used() {
+    test_cache_evicts_least_recently_used(StubUserStore::new());
+}
+
+#[test]
+fn test_mysql_store_invalidates() {
+    test_store_invalidates(mysql_user_store());
+}
+
+#[test]
+fn test_stub_store_invalidates() {
+    test_store_invalidates(StubUserStore::new());
+}
+
+#[test]
+fn test_mysql_cache_ignores_username_case() {
+    test_cache_ignores_username_case(mysql_user_store());
+}
+
+#[test]
+fn test_stub_cache_ignores_username_case() {
+    test_cache_ignores_username_case(StubUserStore::new());
+}
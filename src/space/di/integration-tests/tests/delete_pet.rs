@@ -0,0 +1,50 @@
+use std::str::FromStr;
+
+use integration_tests::{
+    mysql::{MySql, MySqlConfig, MySqlPetStore, MySqlUserStore},
+    pet_store::{Pet, PetStore, PetStoreError},
+    stub::{StubPetStore, StubUserStore},
+    user_store::{User, UserStore},
+};
+use newtypes::*;
+
+fn test_delete_pet<U: UserStore, P: PetStore>(user_store: U, pet_store: P) {
+    let daniel = User {
+        username: Username::from_str("Daniel").unwrap(),
+        email_address: EmailAddress::from_str("daniel@example.com").unwrap(),
+    };
+    user_store.store(&daniel).unwrap();
+
+    let yuki = Pet {
+        name: String::from("Yuki"),
+        carer: daniel.username.clone(),
+    };
+    pet_store.store(&yuki).unwrap();
+
+    assert!(pet_store.delete(&yuki).is_ok());
+    assert_eq!(pet_store.get_by_carer(&daniel.username).unwrap(), vec![]);
+
+    let error = pet_store.delete(&yuki).unwrap_err();
+    assert!(matches!(
+        error.downcast_ref(),
+        Some(PetStoreError::PetNotFound)
+    ));
+}
+
+#[test]
+fn test_mysql_delete_pet() {
+    let config = MySqlConfig::from_env().unwrap();
+    let mysql = MySql::connect(config).unwrap();
+    let mysql_user_store = MySqlUserStore::new(mysql.clone());
+    let mysql_pet_store = MySqlPetStore::new(mysql);
+
+    test_delete_pet(mysql_user_store, mysql_pet_store);
+}
+
+#[test]
+fn test_stub_delete_pet() {
+    let stub_user_store = StubUserStore::new();
+    let stub_pet_store = StubPetStore::new(&stub_user_store);
+
+    test_delete_pet(&stub_user_store, stub_pet_store);
+}
@@ -0,0 +1,128 @@
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use integration_tests::{
+    fault::{FaultyUserStore, InjectedFault},
+    mysql::{MySql, MySqlConfig, MySqlUserStore},
+    stub::StubUserStore,
+    user_store::{User, UserStore},
+};
+use newtypes::*;
+
+fn user(name: &str) -> User {
+    User {
+        username: Username::from_str(name).unwrap(),
+        email_address: EmailAddress::from_str(&format!("{name}@example.com")).unwrap(),
+    }
+}
+
+/// Which of `calls` lookups fail, as a string of `.` and `x` for easy reading.
+fn failures<U: UserStore>(user_store: &U, calls: usize) -> String {
+    let daniel = user("daniel");
+    (0..calls)
+        .map(|_| match user_store.get_by_username(&daniel.username) {
+            Ok(_) => '.',
+            Err(error) => {
+                assert!(error.downcast_ref::<InjectedFault>().is_some());
+                'x'
+            }
+        })
+        .collect()
+}
+
+fn test_every_nth_call_fails<U: UserStore>(user_store: U) {
+    user_store.store(&user("daniel")).unwrap();
+    let faulty = FaultyUserStore::new(user_store).with_every_nth_call_failing(3);
+
+    assert_eq!(failures(&faulty, 9), "..x..x..x");
+
+    // Every method counts towards the same total
+    assert_eq!(failures(&faulty, 2), "..");
+    let error = faulty.store(&user("ted")).unwrap_err();
+    assert_eq!(
+        error.downcast_ref(),
+        Some(&InjectedFault {
+            method: "store",
+            call: 12
+        })
+    );
+}
+
+fn test_seeded_failures_repeat<U: UserStore>(first: U, second: U) {
+    first.store(&user("daniel")).unwrap();
+    second.store(&user("daniel")).unwrap();
+    let first = FaultyUserStore::new(first).with_failure_rate(0.5, 42);
+    let second = FaultyUserStore::new(second).with_failure_rate(0.5, 42);
+
+    let pattern = failures(&first, 50);
+    assert_eq!(pattern, failures(&second, 50));
+    assert!(pattern.contains('x') && pattern.contains('.'));
+
+    let never = FaultyUserStore::new(StubUserStore::new()).with_failure_rate(0.0, 42);
+    never.store(&user("daniel")).unwrap();
+    assert_eq!(failures(&never, 10), "..........");
+}
+
+fn test_partial_store_failure<U: UserStore>(user_store: U) {
+    let faulty = FaultyUserStore::new(user_store)
+        .with_every_nth_call_failing(1)
+        .with_partial_store_failure(true);
+
+    assert!(faulty.store(&user("daniel")).is_err());
+
+    // The store said it failed, but Daniel made it in regardless
+    let user_store = faulty.into_inner();
+    assert_eq!(
+        user_store
+            .get_by_username(&user("daniel").username)
+            .unwrap(),
+        user("daniel")
+    );
+}
+
+fn mysql_user_store() -> MySqlUserStore {
+    let config = MySqlConfig::from_env().unwrap();
+    MySqlUserStore::new(MySql::connect(config).unwrap())
+}
+
+#[test]
+fn test_mysql_every_nth_call_fails() {
+    test_every_nth_call_fails(mysql_user_store());
+}
+
+#[test]
+fn test_stub_every_nth_call_fails() {
+    test_every_nth_call_fails(StubUserStore::new());
+}
+
+#[test]
+fn test_mysql_seeded_failures_repeat() {
+    test_seeded_failures_repeat(mysql_user_store(), mysql_user_store());
+}
+
+#[test]
+fn test_stub_seeded_failures_repeat() {
+    test_seeded_failures_repeat(StubUserStore::new(), StubUserStore::new());
+}
+
+#[test]
+fn test_mysql_partial_store_failure() {
+    test_partial_store_failure(mysql_user_store());
+}
+
+#[test]
+fn test_stub_partial_store_failure() {
+    test_partial_store_failure(StubUserStore::new());
+}
+
+#[test]
+fn test_latency() {
+    let faulty = FaultyUserStore::new(StubUserStore::new()).with_latency(Duration::from_millis(20));
+
+    let start = Instant::now();
+    assert!(faulty.store(&user("daniel")).is_ok());
+    assert!(faulty.get_by_email(&user("daniel").email_address).is_ok());
+
+    assert!(start.elapsed() >= Duration::from_millis(40));
+    assert_eq!(faulty.calls(), 2);
+}
@@ -0,0 +1,111 @@
+use std::str::FromStr;
+
+use integration_tests::{
+    mysql::{MySql, MySqlConfig, MySqlPetStore, MySqlUserStore},
+    pet_store::{Pet, PetStore, PetStoreError},
+    stub::{StubPetStore, StubUserStore},
+    user_store::{User, UserStore},
+};
+use newtypes::*;
+
+fn test_get_pets_by_carer<U: UserStore, P: PetStore>(user_store: U, pet_store: P) {
+    let daniel = User {
+        username: Username::from_str("Daniel").unwrap(),
+        email_address: EmailAddress::from_str("daniel@example.com").unwrap(),
+    };
+    let ted = User {
+        username: Username::from_str("Ted").unwrap(),
+        email_address: EmailAddress::from_str("ted@example.com").unwrap(),
+    };
+    user_store.store(&daniel).unwrap();
+    user_store.store(&ted).unwrap();
+
+    let yuki = Pet {
+        name: String::from("Yuki"),
+        carer: daniel.username.clone(),
+    };
+    let mochi = Pet {
+        name: String::from("Mochi"),
+        carer: daniel.username.clone(),
+    };
+    assert!(pet_store.store(&yuki).is_ok());
+    assert!(pet_store.store(&mochi).is_ok());
+
+    let mut pets = pet_store.get_by_carer(&daniel.username).unwrap();
+    pets.sort_by(|a, b| a.name.cmp(&b.name));
+    assert_eq!(pets, vec![mochi, yuki]);
+    assert_eq!(pet_store.get_by_carer(&ted.username).unwrap(), vec![]);
+
+    let error = pet_store
+        .get_by_carer(&Username::from_str("Nobody").unwrap())
+        .unwrap_err();
+    assert!(matches!(
+        error.downcast_ref(),
+        Some(PetStoreError::CarerNotFound)
+    ));
+}
+
+fn test_store_pet_without_carer<U: UserStore, P: PetStore>(user_store: U, pet_store: P) {
+    let daniel = User {
+        username: Username::from_str("Daniel").unwrap(),
+        email_address: EmailAddress::from_str("daniel@example.com").unwrap(),
+    };
+    user_store.store(&daniel).unwrap();
+
+    let stray = Pet {
+        name: String::from("Stray"),
+        carer: Username::from_str("Nobody").unwrap(),
+    };
+    let error = pet_store.store(&stray).unwrap_err();
+    assert!(matches!(
+        error.downcast_ref(),
+        Some(PetStoreError::CarerNotFound)
+    ));
+
+    let yuki = Pet {
+        name: String::from("Yuki"),
+        carer: daniel.username.clone(),
+    };
+    pet_store.store(&yuki).unwrap();
+    let error = pet_store.store(&yuki).unwrap_err();
+    assert!(matches!(
+        error.downcast_ref(),
+        Some(PetStoreError::PetExists)
+    ));
+}
+
+#[test]
+fn test_mysql_get_pets_by_carer() {
+    let config = MySqlConfig::from_env().unwrap();
+    let mysql = MySql::connect(config).unwrap();
+    let mysql_user_store = MySqlUserStore::new(mysql.clone());
+    let mysql_pet_store = MySqlPetStore::new(mysql);
+
+    test_get_pets_by_carer(mysql_user_store, mysql_pet_store);
+}
+
+#[test]
+fn test_stub_get_pets_by_carer() {
+    let stub_user_store = StubUserStore::new();
+    let stub_pet_store = StubPetStore::new(&stub_user_store);
+
+    test_get_pets_by_carer(&stub_user_store, stub_pet_store);
+}
+
+#[test]
+fn test_mysql_store_pet_without_carer() {
+    let config = MySqlConfig::from_env().unwrap();
+    let mysql = MySql::connect(config).unwrap();
+    let mysql_user_store = MySqlUserStore::new(mysql.clone());
+    let mysql_pet_store = MySqlPetStore::new(mysql);
+
+    test_store_pet_without_carer(mysql_user_store, mysql_pet_store);
+}
+
+#[test]
+fn test_stub_store_pet_without_carer() {
+    let stub_user_store = StubUserStore::new();
+    let stub_pet_store = StubPetStore::new(&stub_user_store);
+
+    test_store_pet_without_carer(&stub_user_store, stub_pet_store);
+}
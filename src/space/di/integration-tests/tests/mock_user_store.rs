@@ -0,0 +1,105 @@
+use std::str::FromStr;
+
+use integration_tests::{
+    mock::{Call, MockUserStore},
+    user_store::{User, UserStore, UserStoreError},
+};
+use newtypes::*;
+
+fn daniel() -> User {
+    User {
+        username: Username::from_str("Daniel").unwrap(),
+        email_address: EmailAddress::from_str("daniel@example.com").unwrap(),
+    }
+}
+
+/// The sort of service we'd want to test with a mock: it looks a user up by
+/// email address, falling back to their username.
+fn find_user<U: UserStore>(
+    user_store: &U,
+    email: &EmailAddress,
+    username: &Username,
+) -> anyhow::Result<User> {
+    user_store
+        .get_by_email(email)
+        .or_else(|_| user_store.get_by_username(username))
+}
+
+#[test]
+fn test_mock_falls_back_to_username() {
+    let daniel = daniel();
+    let mut user_store = MockUserStore::new();
+    user_store
+        .expect_get_by_email()
+        .with(daniel.email_address.clone())
+        .once()
+        .returning(|_| Err(UserStoreError::UserNotFound.into()));
+    let found = daniel.clone();
+    user_store
+        .expect_get_by_username()
+        .with(daniel.username.clone())
+        .once()
+        .returning(move |_| Ok(found.clone()));
+
+    let user = find_user(&user_store, &daniel.email_address, &daniel.username).unwrap();
+
+    assert_eq!(user, daniel);
+    assert_eq!(
+        user_store.calls(),
+        vec![
+            Call::GetByEmail(daniel.email_address.clone()),
+            Call::GetByUsername(daniel.username.clone()),
+        ]
+    );
+}
+
+#[test]
+fn test_mock_skips_fallback() {
+    let daniel = daniel();
+    let mut user_store = MockUserStore::new();
+    let found = daniel.clone();
+    user_store
+        .expect_get_by_email()
+        .once()
+        .returning(move |_| Ok(found.clone()));
+    user_store.expect_get_by_username().never();
+
+    assert!(find_user(&user_store, &daniel.email_address, &daniel.username).is_ok());
+    user_store.verify();
+}
+
+#[test]
+fn test_mock_store_error() {
+    let mut user_store = MockUserStore::new();
+    user_store
+        .expect_store()
+        .times(2)
+        .returning(|_| Err(UserStoreError::UsernameExists.into()));
+
+    for _ in 0..2 {
+        let error = user_store.store(&daniel()).unwrap_err();
+        assert!(matches!(
+            error.downcast_ref(),
+            Some(UserStoreError::UsernameExists)
+        ));
+    }
+}
+
+#[test]
+#[should_panic(
+    expected = "expected get_by_email(EmailAddress(\"daniel@example.com\")) to be called 1 time(s) but it was called 0"
+)]
+fn test_mock_unmet_expectation_panics_on_drop() {
+    let mut user_store = MockUserStore::new();
+    user_store
+        .expect_get_by_email()
+        .with(daniel().email_address)
+        .once();
+}
+
+#[test]
+#[should_panic(expected = "unexpected call to store")]
+fn test_mock_unexpected_call_panics() {
+    let user_store = MockUserStore::new();
+    let _ = user_store.store(&daniel());
+}
@@ -0,0 +1,207 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::str::FromStr;
+use std::time::Duration;
+
+use fake_database::di::DatabaseError;
+use integration_tests::{
+    fault::{FaultyUserStore, InjectedFault},
+    mock::MockUserStore,
+    mysql::{MySql, MySqlConfig, MySqlUserStore},
+    retry::{RetryingUserStore, is_transient},
+    stub::StubUserStore,
+    time_source::FakeTime,
+    user_store::{User, UserStore, UserStoreError},
+};
+use newtypes::*;
+
+fn user(name: &str) -> User {
+    User {
+        username: Username::from_str(name).unwrap(),
+        email_address: EmailAddress::from_str(&format!("{name}@example.com")).unwrap(),
+    }
+}
+
+fn millis(millis: &[u64]) -> Vec<Duration> {
+    millis.iter().copied().map(Duration::from_millis).collect()
+}
+
+#[test]
+fn test_retries_transient_errors() {
+    let daniel = user("daniel");
+    let mut user_store = MockUserStore::new();
+    user_store.expect_get_by_email().times(2).returning(|_| {
+        Err(InjectedFault {
+            method: "get_by_email",
+            call: 0,
+        }
+        .into())
+    });
+    let found = daniel.clone();
+    user_store
+        .expect_get_by_email()
+        .once()
+        .returning(move |_| Ok(found.clone()));
+
+    let time = FakeTime::new();
+    let retries = Rc::new(RefCell::new(Vec::new()));
+    let retrying = RetryingUserStore::new(user_store)
+        .without_jitter()
+        .with_delays(Duration::from_millis(50), Duration::from_secs(1))
+        .with_on_retry({
+            let retries = retries.clone();
+            move |retry| {
+                retries
+                    .borrow_mut()
+                    .push((retry.method, retry.attempt, retry.delay))
+            }
+        })
+        .with_time_source(&time);
+
+    assert_eq!(
+        retrying.get_by_email(&daniel.email_address).unwrap(),
+        daniel
+    );
+    assert_eq!(time.sleeps(), millis(&[50, 100]));
+    assert_eq!(
+        *retries.borrow(),
+        vec![
+            ("get_by_email", 1, Duration::from_millis(50)),
+            ("get_by_email", 2, Duration::from_millis(100)),
+        ]
+    );
+}
+
+#[test]
+fn test_gives_up_after_max_attempts() {
+    let faulty = FaultyUserStore::new(StubUserStore::new()).with_every_nth_call_failing(1);
+    let time = FakeTime::new();
+    let retrying = RetryingUserStore::new(&faulty)
+        .with_max_attempts(4)
+        .without_jitter()
+        .with_delays(Duration::from_millis(50), Duration::from_millis(150))
+        .with_time_source(&time);
+
+    let error = retrying.store(&user("daniel")).unwrap_err();
+
+    assert_eq!(
+        error.downcast_ref(),
+        Some(&InjectedFault {
+            method: "store",
+            call: 4
+        })
+    );
+    assert_eq!(faulty.calls(), 4);
+    // The waits stop growing at the maximum
+    assert_eq!(time.sleeps(), millis(&[50, 100, 150]));
+}
+
+#[test]
+fn test_jitter_is_seeded() {
+    let sleeps = |seed| {
+        let faulty = FaultyUserStore::new(StubUserStore::new()).with_every_nth_call_failing(1);
+        let time = FakeTime::new();
+        let retrying = RetryingUserStore::new(faulty)
+            .with_max_attempts(5)
+            .with_delays(Duration::from_millis(100), Duration::from_secs(10))
+            .with_jitter_seed(seed)
+            .with_time_source(&time);
+        assert!(retrying.get_by_username(&user("daniel").username).is_err());
+        time.sleeps()
+    };
+
+    let first = sleeps(7);
+    assert_eq!(first, sleeps(7));
+    assert_ne!(first, sleeps(8));
+    for (sleep, backoff) in first.iter().zip(millis(&[100, 200, 400, 800])) {
+        assert!(*sleep >= backoff / 2 && *sleep <= backoff);
+    }
+}
+
+fn test_never_retries_permanent_errors<U: UserStore>(user_store: U) {
+    let daniel = user("daniel");
+    let faulty = FaultyUserStore::new(user_store);
+    let time = FakeTime::new();
+    let retrying = RetryingUserStore::new(&faulty).with_time_source(&time);
+
+    retrying.store(&daniel).unwrap();
+    let error = retrying.store(&daniel).unwrap_err();
+    assert!(matches!(
+        error.downcast_ref(),
+        Some(UserStoreError::EmailAddressExists)
+    ));
+    let error = retrying.get_by_username(&user("ted").username).unwrap_err();
+    assert!(matches!(
+        error.downcast_ref(),
+        Some(UserStoreError::UserNotFound)
+    ));
+
+    assert_eq!(faulty.calls(), 3);
+    assert!(time.sleeps().is_empty());
+}
+
+fn test_retried_partial_store<U: UserStore>(user_store: U) {
+    let daniel = user("daniel");
+    // The lookup gets through, then the store goes through but reports failure
+    let faulty = FaultyUserStore::new(user_store)
+        .with_every_nth_call_failing(2)
+        .with_partial_store_failure(true);
+    let time = FakeTime::new();
+    let retrying = RetryingUserStore::new(&faulty).with_time_source(&time);
+
+    assert!(retrying.get_by_username(&daniel.username).is_err());
+    let error = retrying.store(&daniel).unwrap_err();
+
+    // Retrying found the first attempt had worked after all
+    assert!(matches!(
+        error.downcast_ref(),
+        Some(UserStoreError::EmailAddressExists)
+    ));
+    assert_eq!(time.sleeps().len(), 1);
+}
+
+fn mysql_user_store() -> MySqlUserStore {
+    let config = MySqlConfig::from_env().unwrap();
+    MySqlUserStore::new(MySql::connect(config).unwrap())
+}
+
+#[test]
+fn test_mysql_never_retries_permanent_errors() {
+    test_never_retries_permanent_errors(mysql_user_store());
+}
+
+#[test]
+fn test_stub_never_retries_permanent_errors() {
+    test_never_retries_permanent_errors(StubUserStore::new());
+}
+
+#[test]
+fn test_mysql_retried_partial_store() {
+    test_retried_partial_store(mysql_user_store());
+}
+
+#[test]
+fn test_stub_retried_partial_store() {
+    test_retried_partial_store(StubUserStore::new());
+}
+
+#[test]
+fn test_transient_errors() {
+    let transient = [
+        DatabaseError::ConnectionLost.into(),
+        DatabaseError::TransactionConflict.into(),
+        InjectedFault {
+            method: "store",
+            call: 1,
+        }
+        .into(),
+    ];
+    let permanent: [anyhow::Error; 3] = [
+        UserStoreError::UsernameExists.into(),
+        UserStoreError::UserNotFound.into(),
+        DatabaseError::NoSuchTable("users".to_string()).into(),
+    ];
+
+    assert!(transient.iter().all(is_transient));
+    assert!(!permanent.iter().any(is_transient));
+}
@@ -0,0 +1,44 @@
+use std::str::FromStr;
+
+use integration_tests::user_store::User;
+use newtypes::*;
+
+#[test]
+fn test_user_round_trips_through_json() {
+    let user = User {
+        username: Username::from_str("Daniel").unwrap(),
+        email_address: EmailAddress::from_str("daniel@example.com").unwrap(),
+    };
+
+    let json = serde_json::to_string(&user).unwrap();
+    assert_eq!(
+        json,
+        r#"{"username":"Daniel","email_address":"daniel@example.com"}"#
+    );
+    assert_eq!(serde_json::from_str::<User>(&json).unwrap(), user);
+}
+
+#[test]
+fn test_user_with_invalid_email_is_rejected() {
+    let json = r#"{"username":"Daniel","email_address":"daniel@"}"#;
+
+    let error = serde_json::from_str::<User>(json).unwrap_err();
+    assert!(error.is_data());
+    assert!(
+        error
+            .to_string()
+            .starts_with("Email address has nothing after the @")
+    );
+}
+
+#[test]
+fn test_user_with_invalid_username_is_rejected() {
+    let json = r#"{"username":" Daniel","email_address":"daniel@example.com"}"#;
+
+    let error = serde_json::from_str::<User>(json).unwrap_err();
+    assert!(
+        error
+            .to_string()
+            .starts_with("Username can't start or end with whitespace")
+    );
+}
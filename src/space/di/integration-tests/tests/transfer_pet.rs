@@ -0,0 +1,77 @@
+use std::str::FromStr;
+
+use integration_tests::{
+    mysql::{MySql, MySqlConfig, MySqlPetStore, MySqlUserStore},
+    pet_store::{Pet, PetStore, PetStoreError},
+    stub::{StubPetStore, StubUserStore},
+    user_store::{User, UserStore},
+};
+use newtypes::*;
+
+fn test_transfer_pet<U: UserStore, P: PetStore>(user_store: U, pet_store: P) {
+    let daniel = User {
+        username: Username::from_str("Daniel").unwrap(),
+        email_address: EmailAddress::from_str("daniel@example.com").unwrap(),
+    };
+    let ted = User {
+        username: Username::from_str("Ted").unwrap(),
+        email_address: EmailAddress::from_str("ted@example.com").unwrap(),
+    };
+    user_store.store(&daniel).unwrap();
+    user_store.store(&ted).unwrap();
+
+    let yuki = Pet {
+        name: String::from("Yuki"),
+        carer: daniel.username.clone(),
+    };
+    pet_store.store(&yuki).unwrap();
+
+    let transferred = pet_store.transfer(&yuki, &ted.username).unwrap();
+    assert_eq!(transferred.carer, ted.username);
+    assert_eq!(pet_store.get_by_carer(&daniel.username).unwrap(), vec![]);
+    assert_eq!(
+        pet_store.get_by_carer(&ted.username).unwrap(),
+        vec![transferred.clone()]
+    );
+
+    // Daniel no longer looks after Yuki so can't give her away
+    let error = pet_store.transfer(&yuki, &daniel.username).unwrap_err();
+    assert!(matches!(
+        error.downcast_ref(),
+        Some(PetStoreError::PetNotFound)
+    ));
+
+    let error = pet_store
+        .transfer(&transferred, &Username::from_str("Nobody").unwrap())
+        .unwrap_err();
+    assert!(matches!(
+        error.downcast_ref(),
+        Some(PetStoreError::CarerNotFound)
+    ));
+
+    // Ted can't look after two pets called Yuki
+    pet_store.store(&yuki).unwrap();
+    let error = pet_store.transfer(&yuki, &ted.username).unwrap_err();
+    assert!(matches!(
+        error.downcast_ref(),
+        Some(PetStoreError::PetExists)
+    ));
+}
+
+#[test]
+fn test_mysql_transfer_pet() {
+    let config = MySqlConfig::from_env().unwrap();
+    let mysql = MySql::connect(config).unwrap();
+    let mysql_user_store = MySqlUserStore::new(mysql.clone());
+    let mysql_pet_store = MySqlPetStore::new(mysql);
+
+    test_transfer_pet(mysql_user_store, mysql_pet_store);
+}
+
+#[test]
+fn test_stub_transfer_pet() {
+    let stub_user_store = StubUserStore::new();
+    let stub_pet_store = StubPetStore::new(&stub_user_store);
+
+    test_transfer_pet(&stub_user_store, stub_pet_store);
+}
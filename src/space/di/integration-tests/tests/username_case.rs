@@ -0,0 +1,75 @@
+use std::str::FromStr;
+
+use integration_tests::{
+    mysql::{MySql, MySqlConfig, MySqlPetStore, MySqlUserStore},
+    pet_store::{Pet, PetStore, PetStoreError},
+    stub::{StubPetStore, StubUserStore},
+    user_store::{User, UserStore, UserStoreError},
+};
+use newtypes::*;
+
+/// Usernames compare case-insensitively, so every store has to find and
+/// refuse them whatever case they're given in.
+fn test_usernames_ignore_case<U: UserStore, P: PetStore>(user_store: U, pet_store: P) {
+    let daniel = User {
+        username: Username::from_str("Daniel").unwrap(),
+        email_address: EmailAddress::from_str("daniel@example.com").unwrap(),
+    };
+    user_store.store(&daniel).unwrap();
+
+    let shouting = User {
+        username: Username::from_str("DANIEL").unwrap(),
+        email_address: EmailAddress::from_str("shouting@example.com").unwrap(),
+    };
+    let error = user_store.store(&shouting).unwrap_err();
+    assert!(matches!(
+        error.downcast_ref(),
+        Some(UserStoreError::UsernameExists)
+    ));
+
+    let found = user_store
+        .get_by_username(&Username::from_str("daniel").unwrap())
+        .unwrap();
+    // Case is kept as it was stored
+    assert_eq!(found.username.as_str(), "Daniel");
+
+    let yuki = Pet {
+        name: String::from("Yuki"),
+        carer: Username::from_str("dAnIeL").unwrap(),
+    };
+    pet_store.store(&yuki).unwrap();
+    assert_eq!(
+        pet_store.get_by_carer(&daniel.username).unwrap(),
+        vec![yuki]
+    );
+
+    let also_yuki = Pet {
+        name: String::from("Yuki"),
+        carer: daniel.username.clone(),
+    };
+    let error = pet_store.store(&also_yuki).unwrap_err();
+    assert!(matches!(
+        error.downcast_ref(),
+        Some(PetStoreError::PetExists)
+    ));
+    pet_store.delete(&also_yuki).unwrap();
+    assert_eq!(pet_store.get_by_carer(&daniel.username).unwrap(), vec![]);
+}
+
+#[test]
+fn test_mysql_usernames_ignore_case() {
+    let config = MySqlConfig::from_env().unwrap();
+    let mysql = MySql::connect(config).unwrap();
+    let mysql_user_store = MySqlUserStore::new(mysql.clone());
+    let mysql_pet_store = MySqlPetStore::new(mysql);
+
+    test_usernames_ignore_case(mysql_user_store, mysql_pet_store);
+}
+
+#[test]
+fn test_stub_usernames_ignore_case() {
+    let stub_user_store = StubUserStore::new();
+    let stub_pet_store = StubPetStore::new(&stub_user_store);
+
+    test_usernames_ignore_case(&stub_user_store, stub_pet_store);
+}
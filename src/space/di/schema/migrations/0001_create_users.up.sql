@@ -0,0 +1,4 @@
+CREATE TABLE users (
+  email_address VARCHAR(255) NOT NULL UNIQUE,
+  username VARCHAR(255) NOT NULL UNIQUE
+);
@@ -0,0 +1,6 @@
+-- A carer can't look after two pets with the same name
+CREATE TABLE pets (
+  carer VARCHAR(255) NOT NULL REFERENCES users (username),
+  name VARCHAR(255) NOT NULL,
+  PRIMARY KEY (carer, name)
+);
@@ -0,0 +1,2 @@
+ALTER TABLE pets DROP COLUMN carer_key;
+ALTER TABLE users DROP COLUMN username_key;
@@ -0,0 +1,12 @@
+-- Usernames compare case-insensitively, so users are unique by, and pets
+-- refer to their carer by, the lower cased username_key rather than the
+-- username as it was typed
+ALTER TABLE users ADD COLUMN username_key VARCHAR(255) NOT NULL DEFAULT '';
+UPDATE users SET username_key = LOWER(username);
+ALTER TABLE users ADD UNIQUE (username_key);
+
+ALTER TABLE pets ADD COLUMN carer_key VARCHAR(255) NOT NULL DEFAULT '';
+UPDATE pets SET carer_key = LOWER(carer);
+ALTER TABLE pets ADD FOREIGN KEY (carer_key) REFERENCES users (username_key);
+-- A carer still can't look after two pets with the same name
+ALTER TABLE pets ADD UNIQUE (carer_key, name);
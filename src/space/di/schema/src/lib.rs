@@ -0,0 +1,26 @@
+//! The database schema the examples share, so `with-di` and the SQL backed
+//! stores in `integration-tests` run the same migrations.
+
+use fake_database::di::Migration;
+
+/// Every migration, oldest first.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "create_users",
+        up: include_str!("../migrations/0001_create_users.up.sql"),
+        down: include_str!("../migrations/0001_create_users.down.sql"),
+    },
+    Migration {
+        version: 2,
+        name: "create_pets",
+        up: include_str!("../migrations/0002_create_pets.up.sql"),
+        down: include_str!("../migrations/0002_create_pets.down.sql"),
+    },
+    Migration {
+        version: 3,
+        name: "add_username_keys",
+        up: include_str!("../migrations/0003_add_username_keys.up.sql"),
+        down: include_str!("../migrations/0003_add_username_keys.down.sql"),
+    },
+];
@@ -0,0 +1,78 @@
+use fake_database::di::{Executor, Migration, MigrationError, Migrator, MySql};
+use schema::MIGRATIONS;
+
+fn tables(mysql: &MySql) -> Vec<&'static str> {
+    ["users", "pets"]
+        .into_iter()
+        .filter(|table| mysql.query(format!("SELECT * FROM {table}"), &[]).is_ok())
+        .collect()
+}
+
+#[test]
+fn test_migrations_apply_and_revert() {
+    let mysql = MySql::in_memory().unwrap();
+    let migrator = Migrator::new(MIGRATIONS);
+
+    assert_eq!(migrator.up(&mysql).unwrap(), vec![1, 2, 3]);
+    assert_eq!(tables(&mysql), vec!["users", "pets"]);
+
+    assert_eq!(migrator.down(&mysql, 0).unwrap(), vec![3, 2, 1]);
+    assert_eq!(tables(&mysql), Vec::<&str>::new());
+
+    // Everything can be put back again afterwards
+    assert_eq!(migrator.up(&mysql).unwrap(), vec![1, 2, 3]);
+    assert_eq!(migrator.applied(&mysql).unwrap(), vec![1, 2, 3]);
+}
+
+#[test]
+fn test_username_keys_are_filled_in_for_existing_rows() {
+    let mysql = MySql::in_memory().unwrap();
+    Migrator::new(&MIGRATIONS[..2]).up(&mysql).unwrap();
+    mysql
+        .execute(
+            "INSERT INTO users (email_address, username) VALUES ('daniel@example.com', 'Daniel')",
+            &[],
+        )
+        .unwrap();
+    mysql
+        .execute(
+            "INSERT INTO pets (carer, name) VALUES ('Daniel', 'Yuki')",
+            &[],
+        )
+        .unwrap();
+
+    assert_eq!(Migrator::new(MIGRATIONS).up(&mysql).unwrap(), vec![3]);
+
+    let users = mysql
+        .query(
+            "SELECT username FROM users WHERE username_key = 'daniel'",
+            &[],
+        )
+        .unwrap();
+    assert_eq!(users.len(), 1);
+    let pets = mysql
+        .query("SELECT name FROM pets WHERE carer_key = 'daniel'", &[])
+        .unwrap();
+    assert_eq!(pets.len(), 1);
+}
+
+#[test]
+fn test_edited_migration_is_refused() {
+    let mysql = MySql::in_memory().unwrap();
+    Migrator::new(MIGRATIONS).up(&mysql).unwrap();
+
+    let edited = Migration {
+        up: "CREATE TABLE users (username VARCHAR(255));",
+        ..MIGRATIONS[0]
+    };
+    let error = Migrator::new(&[edited, MIGRATIONS[1]])
+        .up(&mysql)
+        .unwrap_err();
+    assert_eq!(
+        error.downcast_ref::<MigrationError>(),
+        Some(&MigrationError::ChecksumMismatch {
+            version: 1,
+            name: "create_users".to_string()
+        })
+    );
+}
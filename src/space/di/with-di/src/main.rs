@@ -1,219 +1,304 @@
+use container::{Provide, Singleton};
+use fake_database::di::*;
 use newtypes::*;
+use schema::MIGRATIONS;
 use std::str::FromStr;
+use std::sync::Arc;
 
 #[derive(Debug)]
-pub struct MySqlUsername(String);
-
-impl MySqlUsername {
-    fn from_env<S: AsRef<str>>(env_name: S) -> anyhow::Result<Self> {
-        let _ = env_name;
-        Ok(Self("".to_string()))
-    }
+struct User {
+    email_address: EmailAddress,
+    username: Username,
 }
 
-impl FromStr for MySqlUsername {
-    type Err = ImpossibleError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Self(s.to_string()))
+impl FromRow for User {
+    fn from_row(row: &Row) -> anyhow::Result<Self> {
+        Ok(Self {
+            email_address: row.get::<String, _>("email_address")?.parse()?,
+            username: row.get::<String, _>("username")?.parse()?,
+        })
     }
 }
 
+/// Pets refer to their carer by username rather than holding a whole
+/// [`User`], the database makes sure that user exists.
 #[derive(Debug)]
-pub struct MySqlPassword(String);
-
-impl MySqlPassword {
-    fn from_env<S: AsRef<str>>(env_name: S) -> anyhow::Result<Self> {
-        let _ = env_name;
-        Ok(Self("".to_string()))
-    }
+struct Pet {
+    carer: Username,
+    name: String,
 }
 
-impl FromStr for MySqlPassword {
-    type Err = ImpossibleError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Self(s.to_string()))
+impl FromRow for Pet {
+    fn from_row(row: &Row) -> anyhow::Result<Self> {
+        Ok(Self {
+            carer: row.get::<String, _>("carer")?.parse()?,
+            name: row.get("name")?,
+        })
     }
 }
 
-#[derive(Debug)]
-pub struct MySqlAddress(String);
+struct UserStore {
+    mysql: Arc<MySqlPool>,
+}
 
-impl MySqlAddress {
-    fn from_env<S: AsRef<str>>(env_name: S) -> anyhow::Result<Self> {
-        let _ = env_name;
-        Ok(Self("".to_string()))
+impl UserStore {
+    fn new(mysql: Arc<MySqlPool>) -> Self {
+        Self { mysql }
     }
-}
 
-impl FromStr for MySqlAddress {
-    type Err = ImpossibleError;
+    fn store(&self, user: &User) -> anyhow::Result<()> {
+        self.store_in(&*self.mysql.get()?, user)
+    }
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Self(s.to_string()))
+    /// Stores the user using `executor`, which might be a transaction shared
+    /// with other stores.
+    fn store_in<E: Executor>(&self, executor: &E, user: &User) -> anyhow::Result<()> {
+        executor.execute(
+            "
+                INSERT INTO users
+                  (email_address, username, username_key)
+                VALUES
+                  (?, ?, ?)
+            ",
+            &[
+                user.email_address.as_str().into(),
+                user.username.as_str().into(),
+                user.username.key().into(),
+            ],
+        )?;
+        Ok(())
     }
-}
 
-#[derive(Debug)]
-pub struct MySqlPort(String);
+    fn get_by_email(&self, email: &EmailAddress) -> anyhow::Result<User> {
+        self.mysql
+            .get()?
+            .query_as(
+                "
+                    SELECT email_address, username
+                    FROM users
+                    WHERE email_address = ?
+                ",
+                &[email.as_str().into()],
+            )?
+            .pop()
+            .ok_or_else(|| anyhow::anyhow!("No user with email address {email}"))
+    }
 
-impl MySqlPort {
-    fn from_env<S: AsRef<str>>(env_name: S) -> anyhow::Result<Self> {
-        let _ = env_name;
-        Ok(Self("".to_string()))
+    fn get_by_username(&self, username: &Username) -> anyhow::Result<User> {
+        self.mysql
+            .get()?
+            .query_as(
+                "
+                    SELECT email_address, username
+                    FROM users
+                    WHERE username_key = ?
+                ",
+                &[username.key().into()],
+            )?
+            .pop()
+            .ok_or_else(|| anyhow::anyhow!("No user with username {username}"))
     }
 }
 
-impl FromStr for MySqlPort {
-    type Err = ImpossibleError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Ok(Self(s.to_string()))
+/// The username as `users` has it, which may differ in case from how it was
+/// asked for. `pets.carer` has to match it exactly, so a pet is stored with
+/// this, or with the username as given if there's no such user, which the
+/// foreign key then refuses.
+fn stored_username<E: Executor>(executor: &E, username: &Username) -> anyhow::Result<Username> {
+    let users = executor.query(
+        "
+            SELECT username
+            FROM users
+            WHERE username_key = ?
+        ",
+        &[username.key().into()],
+    )?;
+    match users.first() {
+        Some(user) => Ok(user.get::<String, _>("username")?.parse()?),
+        None => Ok(username.clone()),
     }
 }
 
-// ----------------------------------------------------------------------------
-
-struct MySqlConfig {
-    address: MySqlAddress,
-    port: MySqlPort,
-    username: MySqlUsername,
-    password: MySqlPassword,
+struct PetStore {
+    mysql: Arc<MySqlPool>,
 }
 
-impl MySqlConfig {
-    fn from_env() -> anyhow::Result<Self> {
-        Ok(Self {
-            address: MySqlAddress::from_env("MYSQL_ADDRESS")?,
-            port: MySqlPort::from_env("MYSQL_PORT")?,
-            username: MySqlUsername::from_env("MYSQL_USERNAME")?,
-            password: MySqlPassword::from_env("MYSQL_PASSWORD")?,
-        })
+impl PetStore {
+    fn new(mysql: Arc<MySqlPool>) -> Self {
+        Self { mysql }
     }
-}
-
-// ----------------------------------------------------------------------------
 
-#[derive(Copy, Clone, Debug)]
-struct MySql {}
-
-impl MySql {
-    fn connect(connection: MySqlConfig) -> anyhow::Result<Self> {
-        let _ = connection.username;
-        let _ = connection.password;
-        let _ = connection.address;
-        let _ = connection.port;
-        Ok(MySql {})
+    fn store(&self, pet: &Pet) -> anyhow::Result<()> {
+        self.store_in(&*self.mysql.get()?, pet)
     }
 
-    fn query<Q, P>(&self, query: Q, parameters: &[P]) -> anyhow::Result<()>
-    where
-        Q: AsRef<str>,
-        P: AsRef<str>,
-    {
-        let _ = query;
-        let _ = parameters;
+    /// Stores the pet using `executor`, which might be a transaction shared
+    /// with other stores.
+    fn store_in<E: Executor>(&self, executor: &E, pet: &Pet) -> anyhow::Result<()> {
+        let carer = stored_username(executor, &pet.carer)?;
+        executor.execute(
+            "
+                INSERT INTO pets
+                  (carer, carer_key, name)
+                VALUES
+                  (?, ?, ?)
+            ",
+            &[
+                carer.as_str().into(),
+                carer.key().into(),
+                pet.name.as_str().into(),
+            ],
+        )?;
         Ok(())
     }
-}
-
-// ----------------------------------------------------------------------------
 
-struct User {
-    email_address: EmailAddress,
-    username: Username,
-}
-
-struct Pet {
-    butler: User,
-    name: String,
-}
-
-struct UserStore {
-    mysql: MySql,
-}
-
-impl UserStore {
-    fn new(mysql: MySql) -> Self {
-        Self { mysql }
-    }
-
-    fn store(&self, user: &User) -> anyhow::Result<()> {
-        self.mysql.query(
+    fn get_by_carer(&self, carer: &Username) -> anyhow::Result<Vec<Pet>> {
+        self.mysql.get()?.query_as(
             "
-                INSERT INTO users
-                  (email_address, username)
-                VALUES
-                  (?, ?)
+                SELECT carer, name
+                FROM pets
+                WHERE carer_key = ?
             ",
-            &[user.email_address.as_str(), user.username.as_str()],
+            &[carer.key().into()],
         )
     }
 
-    fn get_by_email(&self, email: &EmailAddress) -> anyhow::Result<User> {
-        let _ = email;
-        Ok(User {
-            email_address: EmailAddress::from_str("")?,
-            username: Username::from_str("")?,
+    /// Hands `pet` over to `carer`, returning the pet as it is now.
+    fn transfer(&self, pet: &Pet, carer: &Username) -> anyhow::Result<Pet> {
+        let mysql = self.mysql.get()?;
+        let carer = stored_username(&*mysql, carer)?;
+        let transferred = mysql.execute(
+            "
+                UPDATE pets
+                SET carer = ?, carer_key = ?
+                WHERE carer_key = ? AND name = ?
+            ",
+            &[
+                carer.as_str().into(),
+                carer.key().into(),
+                pet.carer.key().into(),
+                pet.name.as_str().into(),
+            ],
+        )?;
+        if transferred == 0 {
+            anyhow::bail!("{} has no pet called {}", pet.carer, pet.name);
+        }
+        Ok(Pet {
+            carer,
+            name: pet.name.clone(),
         })
     }
 
-    fn get_by_username(&self, username: &Username) -> anyhow::Result<User> {
-        let _ = username;
-        Ok(User {
-            email_address: EmailAddress::from_str("")?,
-            username: Username::from_str("")?,
-        })
+    fn delete(&self, pet: &Pet) -> anyhow::Result<()> {
+        let deleted = self.mysql.get()?.execute(
+            "
+                DELETE FROM pets
+                WHERE carer_key = ? AND name = ?
+            ",
+            &[pet.carer.key().into(), pet.name.as_str().into()],
+        )?;
+        if deleted == 0 {
+            anyhow::bail!("{} has no pet called {}", pet.carer, pet.name);
+        }
+        Ok(())
     }
 }
 
-struct PetStore {
-    mysql: MySql,
+/// Everything the application is made of, built on demand. Asking for
+/// something it doesn't know how to provide is a compile error.
+struct App {
+    mysql_config: MySqlConfig,
+    mysql: Singleton<MySqlPool>,
 }
 
-impl PetStore {
-    fn new(mysql: MySql) -> Self {
-        Self { mysql }
+impl App {
+    fn new(mysql_config: MySqlConfig) -> Self {
+        Self {
+            mysql_config,
+            mysql: Singleton::new(),
+        }
     }
+}
 
-    fn store(&self, pet: &Pet) -> anyhow::Result<()> {
-        self.mysql.query(
-            "
-                INSERT INTO pets
-                  (carer, name)
-                VALUES
-                  (?, ?)
-            ",
-            &[pet.butler.username.as_str(), pet.name.as_str()],
-        )
+impl Provide<Arc<MySqlPool>> for App {
+    fn provide(&self) -> anyhow::Result<Arc<MySqlPool>> {
+        self.mysql.get_or_try_init(|| {
+            let mysql = MySqlPool::new(
+                self.mysql_config.clone(),
+                PoolConfig::new().with_max_size(4),
+            );
+            Migrator::new(MIGRATIONS).up(&*mysql.get()?)?;
+            Ok(mysql)
+        })
     }
 }
 
-fn main() -> anyhow::Result<()> {
-    let mysql_config = MySqlConfig::from_env()?;
+impl Provide<UserStore> for App {
+    fn provide(&self) -> anyhow::Result<UserStore> {
+        Ok(UserStore::new(self.provide()?))
+    }
+}
+
+impl Provide<PetStore> for App {
+    fn provide(&self) -> anyhow::Result<PetStore> {
+        Ok(PetStore::new(self.provide()?))
+    }
+}
 
-    let mysql = MySql::connect(mysql_config)?;
+fn main() -> anyhow::Result<()> {
+    let app = App::new(MySqlConfig::from_environment()?);
 
-    let user_store = UserStore::new(mysql);
-    let pet_store = PetStore::new(mysql);
+    let mysql: Arc<MySqlPool> = app.provide()?;
+    let user_store: UserStore = app.provide()?;
+    let pet_store: PetStore = app.provide()?;
 
     let daniel = User {
         username: Username::from_str("Daniel")?,
         email_address: EmailAddress::from_str("daniel@example.com")?,
     };
 
-    user_store.store(&daniel)?;
-
     let yuki = Pet {
-        butler: daniel,
+        carer: daniel.username.clone(),
         name: String::from("Yuki"),
     };
 
-    pet_store.store(&yuki)?;
+    // If storing Yuki fails we don't want Daniel left behind without her
+    mysql.get()?.transaction(|transaction| {
+        user_store.store_in(transaction, &daniel)?;
+        pet_store.store_in(transaction, &yuki)
+    })?;
+
+    let by_email = user_store.get_by_email(&EmailAddress::from_str("daniel@example.com")?)?;
+    let by_username = user_store.get_by_username(&Username::from_str("Daniel")?)?;
+    println!("{by_email:?}");
+    println!("{by_username:?}");
+
+    // Daniel already exists, so giving him another pet is a single write that
+    // doesn't need a transaction, and neither does a user without pets
+    let mochi = Pet {
+        carer: by_username.username,
+        name: String::from("Mochi"),
+    };
+    pet_store.store(&mochi)?;
+    let ted = User {
+        username: Username::from_str("Ted")?,
+        email_address: EmailAddress::from_str("ted@example.com")?,
+    };
+    user_store.store(&ted)?;
+
+    // Nobody can look after a pet without being a user first
+    let stray = Pet {
+        carer: Username::from_str("Nobody")?,
+        name: String::from("Stray"),
+    };
+    if let Err(error) = pet_store.store(&stray) {
+        println!("Couldn't store {}: {error}", stray.name);
+    }
 
-    user_store.get_by_email(&EmailAddress::from_str("")?)?;
-    user_store.get_by_username(&Username::from_str("")?)?;
+    let mochi = pet_store.transfer(&mochi, &ted.username)?;
+    println!("{:?}", pet_store.get_by_carer(&ted.username)?);
+    pet_store.delete(&mochi)?;
+    println!("{:?}", pet_store.get_by_carer(&daniel.username)?);
 
     Ok(())
 }
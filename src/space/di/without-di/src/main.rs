@@ -25,19 +25,33 @@ impl UserStore {
 
         let mysql = MySql::connect(address, port, username, password)?;
 
+        mysql.execute(
+            "
+                CREATE TABLE IF NOT EXISTS users (
+                      email_address VARCHAR(255) NOT NULL UNIQUE,
+                      username VARCHAR(255) NOT NULL UNIQUE
+                )
+            ",
+            &[],
+        )?;
+
         Ok(Self { mysql })
     }
 
     fn store(&self, user: &User) -> anyhow::Result<()> {
-        self.mysql.query(
+        self.mysql.execute(
             "
                 INSERT INTO users
                   (email_address, username)
                 VALUES
                   (?, ?)
             ",
-            &[user.email_address.as_str(), user.username.as_str()],
-        )
+            &[
+                user.email_address.as_str().into(),
+                user.username.as_str().into(),
+            ],
+        )?;
+        Ok(())
     }
 }
 
@@ -54,19 +68,33 @@ impl PetStore {
 
         let mysql = MySql::connect(address, port, username, password)?;
 
+        mysql.execute(
+            "
+                CREATE TABLE IF NOT EXISTS pets (
+                      carer VARCHAR(255) NOT NULL,
+                      name VARCHAR(255) NOT NULL
+                )
+            ",
+            &[],
+        )?;
+
         Ok(Self { mysql })
     }
 
     fn store(&self, pet: &Pet) -> anyhow::Result<()> {
-        self.mysql.query(
+        self.mysql.execute(
             "
                 INSERT INTO pets
                   (carer, name)
                 VALUES
                   (?, ?)
             ",
-            &[pet.butler.username.as_str(), pet.name.as_str()],
-        )
+            &[
+                pet.butler.username.as_str().into(),
+                pet.name.as_str().into(),
+            ],
+        )?;
+        Ok(())
     }
 }
 
@@ -0,0 +1,287 @@
+//! `#[derive(Newtype)]`, for the boilerplate every validated newtype needs.
+//!
+//! Deriving it on a tuple struct with one field, usually a `String`, writes
+//! a `new` constructor that runs the validator, `FromStr`, `Display`,
+//! `TryFrom<String>`, `AsRef` and `into_inner`, plus `as_str` for `String`s.
+//! A `String` with nothing to validate gets `From<String>` instead.
+//! It's configured with a `#[newtype(...)]` attribute:
+//!
+//! - `validate = path` names a function that takes the value and returns it,
+//!   normalized if need be, or an error
+//! - `error = Type` is the validator's error type, which is also what parsing
+//!   returns. Without a validator it defaults to `Infallible` for `String`s,
+//!   and to the field's `FromStr` error otherwise
+//! - `from_env` adds a `from_env(name)` constructor that reads the value from
+//!   an environment variable. `from_env = path` reads it with a function of
+//!   your own instead, which takes the variable's name and returns a
+//!   `Result<String, _>`. Either way it returns an `anyhow::Result`, so the
+//!   crate deriving it needs to depend on `anyhow`
+//!
+//! ```
+//! use newtype_derive::Newtype;
+//!
+//! #[derive(Debug, PartialEq)]
+//! struct BlankName;
+//!
+//! fn validate_name(name: String) -> Result<String, BlankName> {
+//!     match name.trim() {
+//!         "" => Err(BlankName),
+//!         name => Ok(name.to_string()),
+//!     }
+//! }
+//!
+//! #[derive(Debug, Newtype)]
+//! #[newtype(validate = validate_name, error = BlankName)]
+//! struct Name(String);
+//!
+//! assert_eq!(Name::new(" Yuki ").unwrap().as_str(), "Yuki");
+//! assert_eq!("".parse::<Name>().unwrap_err(), BlankName);
+//! ```
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{Data, DeriveInput, Fields, Path, Token, Type, parse_macro_input};
+
+#[proc_macro_derive(Newtype, attributes(newtype))]
+pub fn derive_newtype(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// What the `#[newtype(...)]` attribute asked for.
+#[derive(Default)]
+struct Options {
+    validate: Option<Path>,
+    error: Option<Type>,
+    /// `Some(None)` to read with `std::env::var`, `Some(Some(path))` to read
+    /// with the function at `path`.
+    from_env: Option<Option<Path>>,
+}
+
+impl Options {
+    fn parse(input: &DeriveInput) -> syn::Result<Self> {
+        let mut options = Self::default();
+        for attribute in &input.attrs {
+            if !attribute.path().is_ident("newtype") {
+                continue;
+            }
+            attribute.parse_nested_meta(|meta| {
+                if meta.path.is_ident("validate") {
+                    if options.validate.is_some() {
+                        return Err(meta.error("`validate` is set more than once"));
+                    }
+                    options.validate = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("error") {
+                    if options.error.is_some() {
+                        return Err(meta.error("`error` is set more than once"));
+                    }
+                    options.error = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("from_env") {
+                    if options.from_env.is_some() {
+                        return Err(meta.error("`from_env` is set more than once"));
+                    }
+                    let read = if meta.input.peek(Token![=]) {
+                        Some(meta.value()?.parse()?)
+                    } else {
+                        None
+                    };
+                    options.from_env = Some(read);
+                } else {
+                    return Err(meta.error(
+                        "unknown newtype option, expected `validate`, `error` or `from_env`",
+                    ));
+                }
+                Ok(())
+            })?;
+        }
+
+        if let (Some(validate), None) = (&options.validate, &options.error) {
+            return Err(syn::Error::new_spanned(
+                validate,
+                "`validate` needs an `error` type for it to return",
+            ));
+        }
+        Ok(options)
+    }
+}
+
+/// The type of the struct's only field.
+fn field_type(input: &DeriveInput) -> syn::Result<&Type> {
+    const MESSAGE: &str = "Newtype can only be derived for tuple structs with one field";
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(&input.ident, MESSAGE));
+    };
+    match &data.fields {
+        Fields::Unnamed(fields) if fields.unnamed.len() == 1 => Ok(&fields.unnamed[0].ty),
+        fields => Err(syn::Error::new_spanned(fields, MESSAGE)),
+    }
+}
+
+fn is_string(ty: &Type) -> bool {
+    let Type::Path(path) = ty else {
+        return false;
+    };
+    path.qself.is_none()
+        && path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "String" && segment.arguments.is_none())
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let options = Options::parse(input)?;
+    let field = field_type(input)?;
+    let name = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+    let string = is_string(field);
+
+    let error = match (&options.error, string) {
+        (Some(error), _) => quote!(#error),
+        (None, true) => quote!(::std::convert::Infallible),
+        (None, false) => quote!(<#field as ::std::str::FromStr>::Err),
+    };
+    let validate = options
+        .validate
+        .as_ref()
+        .map(|validate| quote_spanned!(validate.span()=> let value: #field = #validate(value)?;));
+
+    let from_env = options.from_env.as_ref().map(|read| {
+        let read = match read {
+            Some(read) => quote!(#read(name)?),
+            None => quote! {
+                ::std::env::var(name).map_err(|_| {
+                    ::anyhow::anyhow!("Missing Environment variable {name}")
+                })?
+            },
+        };
+        quote! {
+            /// Reads the value from the environment variable `name`.
+            pub fn from_env<S: ::std::convert::AsRef<str>>(name: S) -> ::anyhow::Result<Self> {
+                let name = name.as_ref();
+                let value = #read;
+                Ok(<Self as ::std::str::FromStr>::from_str(&value)?)
+            }
+        }
+    });
+
+    // Nothing can go wrong without a validator, so `From` it is, and
+    // `TryFrom` comes with it
+    let try_from = if options.validate.is_none() && options.error.is_none() {
+        quote! {
+            impl #impl_generics ::std::convert::From<::std::string::String>
+                for #name #type_generics #where_clause
+            {
+                fn from(value: ::std::string::String) -> Self {
+                    Self(value)
+                }
+            }
+        }
+    } else {
+        quote! {
+            impl #impl_generics ::std::convert::TryFrom<::std::string::String>
+                for #name #type_generics #where_clause
+            {
+                type Error = #error;
+
+                fn try_from(value: ::std::string::String) -> ::std::result::Result<Self, Self::Error> {
+                    Self::new(value)
+                }
+            }
+        }
+    };
+
+    let by_type = if string {
+        quote! {
+            impl #impl_generics #name #type_generics #where_clause {
+                pub fn new<S: ::std::convert::Into<::std::string::String>>(
+                    value: S,
+                ) -> ::std::result::Result<Self, #error> {
+                    let value: ::std::string::String = value.into();
+                    #validate
+                    Ok(Self(value))
+                }
+
+                pub fn as_str(&self) -> &str {
+                    &self.0
+                }
+
+                pub fn into_inner(self) -> ::std::string::String {
+                    self.0
+                }
+
+                #from_env
+            }
+
+            impl #impl_generics ::std::str::FromStr for #name #type_generics #where_clause {
+                type Err = #error;
+
+                fn from_str(s: &str) -> ::std::result::Result<Self, Self::Err> {
+                    Self::new(s)
+                }
+            }
+
+            #try_from
+
+            impl #impl_generics ::std::convert::AsRef<str> for #name #type_generics #where_clause {
+                fn as_ref(&self) -> &str {
+                    &self.0
+                }
+            }
+        }
+    } else {
+        quote! {
+            impl #impl_generics #name #type_generics #where_clause {
+                pub fn new(value: #field) -> ::std::result::Result<Self, #error> {
+                    #validate
+                    Ok(Self(value))
+                }
+
+                pub fn into_inner(self) -> #field {
+                    self.0
+                }
+
+                #from_env
+            }
+
+            impl #impl_generics ::std::str::FromStr for #name #type_generics #where_clause {
+                type Err = #error;
+
+                fn from_str(s: &str) -> ::std::result::Result<Self, Self::Err> {
+                    let value: #field = s.parse()?;
+                    Self::new(value)
+                }
+            }
+
+            impl #impl_generics ::std::convert::TryFrom<::std::string::String>
+                for #name #type_generics #where_clause
+            {
+                type Error = #error;
+
+                fn try_from(value: ::std::string::String) -> ::std::result::Result<Self, Self::Error> {
+                    <Self as ::std::str::FromStr>::from_str(&value)
+                }
+            }
+
+            impl #impl_generics ::std::convert::AsRef<#field> for #name #type_generics #where_clause {
+                fn as_ref(&self) -> &#field {
+                    &self.0
+                }
+            }
+        }
+    };
+
+    Ok(quote! {
+        #by_type
+
+        impl #impl_generics ::std::fmt::Display for #name #type_generics #where_clause {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                ::std::fmt::Display::fmt(&self.0, f)
+            }
+        }
+    })
+}
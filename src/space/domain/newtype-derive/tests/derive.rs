@@ -0,0 +1,139 @@
+use newtype_derive::Newtype;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, PartialEq)]
+struct Blank;
+
+impl fmt::Display for Blank {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Name can't be blank")
+    }
+}
+
+impl std::error::Error for Blank {}
+
+fn validate_name(name: String) -> Result<String, Blank> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(Blank);
+    }
+    Ok(name.to_string())
+}
+
+/// A stand-in for the environment, as setting real variables isn't safe
+/// while other tests might be reading them.
+fn fake_env(name: &str) -> anyhow::Result<String> {
+    match name {
+        "NAME" => Ok(" Yuki ".to_string()),
+        "BLANK" => Ok(" ".to_string()),
+        "PORT" => Ok("3306".to_string()),
+        _ => anyhow::bail!("{name} isn't set"),
+    }
+}
+
+#[derive(Debug, PartialEq, Newtype)]
+#[newtype(validate = validate_name, error = Blank, from_env = fake_env)]
+struct Name(String);
+
+#[derive(Debug, PartialEq, Newtype)]
+#[newtype(from_env)]
+struct PackageName(String);
+
+#[derive(Debug, PartialEq, Newtype)]
+struct Note(String);
+
+#[derive(Debug, PartialEq, Newtype)]
+#[newtype(from_env = fake_env)]
+struct Port(u16);
+
+#[derive(Debug, PartialEq)]
+struct NotEven;
+
+impl From<std::num::ParseIntError> for NotEven {
+    fn from(_: std::num::ParseIntError) -> Self {
+        Self
+    }
+}
+
+fn validate_even(number: u32) -> Result<u32, NotEven> {
+    if number % 2 == 1 {
+        return Err(NotEven);
+    }
+    Ok(number)
+}
+
+#[derive(Debug, PartialEq, Newtype)]
+#[newtype(validate = validate_even, error = NotEven)]
+struct Even(u32);
+
+#[test]
+fn test_string_newtype() {
+    let name = Name::new("  Yuki ").unwrap();
+    assert_eq!(name.as_str(), "Yuki");
+    assert_eq!(name.to_string(), "Yuki");
+    assert_eq!(AsRef::<str>::as_ref(&name), "Yuki");
+    assert_eq!(Name::from_str("Yuki"), Ok(name));
+    assert_eq!(
+        Name::try_from("Fio".to_string()).unwrap().into_inner(),
+        "Fio"
+    );
+
+    assert_eq!(Name::new(" "), Err(Blank));
+    assert_eq!(Name::from_str(""), Err(Blank));
+    assert_eq!(Name::try_from(String::new()), Err(Blank));
+}
+
+#[test]
+fn test_string_newtype_without_validation() {
+    let note: Result<Note, std::convert::Infallible> = Note::from_str("");
+    assert_eq!(note.unwrap().as_str(), "");
+    assert_eq!(
+        Note::from("Likes fish".to_string()).to_string(),
+        "Likes fish"
+    );
+}
+
+#[test]
+fn test_parsed_newtype() {
+    assert_eq!(Port::from_str("3306").unwrap().into_inner(), 3306);
+    assert_eq!(*AsRef::<u16>::as_ref(&Port::new(80).unwrap()), 80);
+    assert!(Port::from_str("port").is_err());
+    assert!(Port::try_from("65536".to_string()).is_err());
+
+    assert_eq!(Even::from_str("4"), Ok(Even(4)));
+    assert_eq!(Even::from_str("5"), Err(NotEven));
+    assert_eq!(Even::from_str("four"), Err(NotEven));
+    assert_eq!(Even::new(4).unwrap().to_string(), "4");
+}
+
+#[test]
+fn test_from_env() {
+    assert_eq!(Name::from_env("NAME").unwrap().as_str(), "Yuki");
+    let error = Name::from_env("BLANK").unwrap_err();
+    assert_eq!(error.downcast_ref(), Some(&Blank));
+    let error = Name::from_env("MISSING").unwrap_err();
+    assert_eq!(error.to_string(), "MISSING isn't set");
+
+    assert_eq!(Port::from_env("PORT").unwrap(), Port(3306));
+}
+
+#[test]
+fn test_from_real_env() {
+    // Cargo sets this for the tests it runs
+    assert_eq!(
+        PackageName::from_env("CARGO_PKG_NAME").unwrap().as_str(),
+        "newtype-derive"
+    );
+    let error = PackageName::from_env("NEWTYPE_DERIVE_MISSING").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Missing Environment variable NEWTYPE_DERIVE_MISSING"
+    );
+}
+
+#[test]
+fn test_compile_errors() {
+    let tests = trybuild::TestCases::new();
+    tests.compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,9 @@
+use newtype_derive::Newtype;
+
+#[derive(Newtype)]
+enum Username {
+    Guest,
+    Named(String),
+}
+
+fn main() {}
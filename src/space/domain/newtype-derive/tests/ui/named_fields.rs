@@ -0,0 +1,8 @@
+use newtype_derive::Newtype;
+
+#[derive(Newtype)]
+struct Username {
+    username: String,
+}
+
+fn main() {}
@@ -0,0 +1,6 @@
+use newtype_derive::Newtype;
+
+#[derive(Newtype)]
+struct Address(String, u16);
+
+fn main() {}
@@ -0,0 +1,7 @@
+use newtype_derive::Newtype;
+
+#[derive(Newtype)]
+#[newtype(normalize)]
+struct Username(String);
+
+fn main() {}
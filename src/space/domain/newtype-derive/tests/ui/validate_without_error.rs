@@ -0,0 +1,11 @@
+use newtype_derive::Newtype;
+
+fn validate_username(username: String) -> Result<String, String> {
+    Ok(username)
+}
+
+#[derive(Newtype)]
+#[newtype(validate = validate_username)]
+struct Username(String);
+
+fn main() {}
@@ -0,0 +1,19 @@
+use newtype_derive::Newtype;
+
+#[derive(Debug)]
+struct Blank;
+
+// Validators take the value and hand it back, so this one should take and
+// return a String
+fn validate_username(username: &str) -> Result<(), Blank> {
+    if username.is_empty() {
+        return Err(Blank);
+    }
+    Ok(())
+}
+
+#[derive(Newtype)]
+#[newtype(validate = validate_username, error = Blank)]
+struct Username(String);
+
+fn main() {}
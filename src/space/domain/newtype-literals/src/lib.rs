@@ -0,0 +1,61 @@
+//! `email!` and `username!`, for newtypes written as literals in the code.
+//!
+//! Parsing a literal we know is fine still returns a `Result` that has to be
+//! unwrapped. These macros run the same validation as `FromStr` while
+//! compiling, so an invalid literal fails the build instead, and the macro
+//! gives back the newtype itself, built from the value it checked without
+//! checking it again. The crate using them needs to depend on `newtypes` too.
+//!
+//! ```
+//! use newtype_literals::{email, username};
+//!
+//! let email = email!("yuki@example.com");
+//! assert_eq!(email.domain(), "example.com");
+//! assert_eq!(username!("Yuki").as_str(), "Yuki");
+//! ```
+//!
+//! ```compile_fail
+//! let email = newtype_literals::email!("yuki");
+//! ```
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{LitStr, parse_macro_input};
+
+use newtypes::{EmailAddress, Username};
+
+/// An [`EmailAddress`](newtypes::EmailAddress) checked at compile time.
+#[proc_macro]
+pub fn email(input: TokenStream) -> TokenStream {
+    let literal = parse_macro_input!(input as LitStr);
+    match literal.value().parse::<EmailAddress>() {
+        Ok(email) => {
+            let address = email.as_str();
+            let at = email.local_part().len();
+            quote!(::newtypes::EmailAddress::from_validated_unchecked(#address, #at))
+        }
+        Err(error) => compile_error(&literal, error),
+    }
+    .into()
+}
+
+/// A [`Username`](newtypes::Username) checked at compile time.
+#[proc_macro]
+pub fn username(input: TokenStream) -> TokenStream {
+    let literal = parse_macro_input!(input as LitStr);
+    match literal.value().parse::<Username>() {
+        Ok(username) => {
+            // Normalized already, so it's stored just as parsing would
+            let username = username.as_str();
+            quote!(::newtypes::Username::from_validated_unchecked(#username))
+        }
+        Err(error) => compile_error(&literal, error),
+    }
+    .into()
+}
+
+/// Reports why the literal isn't valid, pointing at the literal.
+fn compile_error(literal: &LitStr, error: impl std::fmt::Display) -> TokenStream2 {
+    syn::Error::new(literal.span(), error).into_compile_error()
+}
@@ -0,0 +1,40 @@
+use std::str::FromStr;
+
+use newtype_literals::{email, username};
+use newtypes::{EmailAddress, Username};
+
+#[test]
+fn test_email() {
+    let email = email!("yuki@example.com");
+    assert_eq!(email, EmailAddress::from_str("yuki@example.com").unwrap());
+    assert_eq!(email.local_part(), "yuki");
+
+    // The @ is found in the normalized address, so a quoted local part
+    // containing one doesn't confuse it
+    let quoted = email!("\"yuki@home\"@example.com");
+    assert_eq!(quoted.local_part(), "\"yuki@home\"");
+    assert_eq!(quoted.domain(), "example.com");
+    assert_eq!(
+        email!("yuki@bücher.example").ascii_domain(),
+        "xn--bcher-kva.example"
+    );
+}
+
+#[test]
+fn test_username() {
+    let username = username!("Yuki");
+    assert_eq!(username, Username::from_str("Yuki").unwrap());
+    assert_eq!(username, username!("yuki"));
+}
+
+#[test]
+fn test_username_is_normalized() {
+    // An e followed by a combining acute accent becomes a single é
+    assert_eq!(username!("Rene\u{301}").as_str(), "Ren\u{e9}");
+}
+
+#[test]
+fn test_compile_errors() {
+    let tests = trybuild::TestCases::new();
+    tests.compile_fail("tests/ui/*.rs");
+}
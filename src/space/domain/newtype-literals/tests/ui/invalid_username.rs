@@ -0,0 +1,5 @@
+use newtype_literals::username;
+
+fn main() {
+    let _ = username!("");
+}
@@ -0,0 +1,6 @@
+use newtype_literals::email;
+
+fn main() {
+    let address = "yuki@example.com";
+    let _ = email!(address);
+}
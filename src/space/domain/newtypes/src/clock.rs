@@ -0,0 +1,39 @@
+use crate::Date;
+
+/// Where "today" comes from, so anything that depends on the date can be
+/// checked on whatever date a test needs.
+pub trait Clock {
+    fn today(&self) -> Date;
+}
+
+impl<C: Clock + ?Sized> Clock for &C {
+    fn today(&self) -> Date {
+        (**self).today()
+    }
+}
+
+/// The real date, in UTC.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn today(&self) -> Date {
+        Date::today()
+    }
+}
+
+/// A clock that's always on the same day.
+#[derive(Debug, Clone, Copy)]
+pub struct FixedClock(Date);
+
+impl FixedClock {
+    pub fn new(today: Date) -> Self {
+        Self(today)
+    }
+}
+
+impl Clock for FixedClock {
+    fn today(&self) -> Date {
+        self.0
+    }
+}
@@ -0,0 +1,294 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::clock::{Clock, SystemClock};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DateError {
+    /// The date isn't written `YYYY-MM-DD`.
+    InvalidFormat,
+    InvalidMonth(u8),
+    InvalidDay {
+        year: i32,
+        month: u8,
+        day: u8,
+    },
+}
+
+impl fmt::Display for DateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidFormat => write!(f, "Date must be written YYYY-MM-DD"),
+            Self::InvalidMonth(month) => write!(f, "There is no month {month}"),
+            Self::InvalidDay { year, month, day } => {
+                write!(f, "There is no day {day} in {year:04}-{month:02}")
+            }
+        }
+    }
+}
+
+impl Error for DateError {}
+
+/// A day in the Gregorian calendar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    year: i32,
+    month: u8,
+    day: u8,
+}
+
+impl Date {
+    pub fn new(year: i32, month: u8, day: u8) -> Result<Self, DateError> {
+        if !(1..=12).contains(&month) {
+            return Err(DateError::InvalidMonth(month));
+        }
+        if day == 0 || day > Self::days_in_month(year, month) {
+            return Err(DateError::InvalidDay { year, month, day });
+        }
+        Ok(Self { year, month, day })
+    }
+
+    /// Today's date in UTC. Prefer taking a [`Clock`] to calling this, so
+    /// tests can pick the date.
+    pub fn today() -> Self {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        Self::from_days_since_epoch((seconds / 86_400) as i64)
+    }
+
+    pub fn year(&self) -> i32 {
+        self.year
+    }
+
+    pub fn month(&self) -> u8 {
+        self.month
+    }
+
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+
+    pub fn is_leap_year(year: i32) -> bool {
+        year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+    }
+
+    /// How many days `month` has in `year`, or 0 if there's no such month.
+    pub fn days_in_month(year: i32, month: u8) -> u8 {
+        match month {
+            1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+            4 | 6 | 9 | 11 => 30,
+            2 if Self::is_leap_year(year) => 29,
+            2 => 28,
+            _ => 0,
+        }
+    }
+
+    /// How many days the date is after 1970-01-01, negative if it's before,
+    /// using Howard Hinnant's `days_from_civil`, which counts in 400 year
+    /// eras starting in March so leap days fall at the end of the year.
+    pub fn days_since_epoch(&self) -> i64 {
+        let month = i64::from(self.month);
+        let year = i64::from(self.year) - i64::from(month <= 2);
+        let era = year.div_euclid(400);
+        let year_of_era = year.rem_euclid(400);
+        let march_month = (month + 9) % 12;
+        let day_of_year = (153 * march_month + 2) / 5 + i64::from(self.day) - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        era * 146_097 + day_of_era - 719_468
+    }
+
+    /// The date `days` after 1970-01-01, the inverse of
+    /// [`Date::days_since_epoch`] using Hinnant's `civil_from_days`. The
+    /// year has to fit in an `i32`.
+    pub fn from_days_since_epoch(days: i64) -> Self {
+        let days = days + 719_468;
+        let era = days.div_euclid(146_097);
+        let day_of_era = days.rem_euclid(146_097);
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let march_month = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * march_month + 2) / 5 + 1;
+        let month = if march_month < 10 {
+            march_month + 3
+        } else {
+            march_month - 9
+        };
+        let year = year_of_era + era * 400 + i64::from(month <= 2);
+        Self {
+            year: year as i32,
+            month: month as u8,
+            day: day as u8,
+        }
+    }
+}
+
+impl FromStr for Date {
+    type Err = DateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        fn number<T: FromStr>(part: Option<&str>, digits: usize) -> Result<T, DateError> {
+            match part {
+                Some(part) if part.len() == digits && part.bytes().all(|b| b.is_ascii_digit()) => {
+                    part.parse().map_err(|_| DateError::InvalidFormat)
+                }
+                _ => Err(DateError::InvalidFormat),
+            }
+        }
+
+        let mut parts = s.split('-');
+        let year = number(parts.next(), 4)?;
+        let month = number(parts.next(), 2)?;
+        let day = number(parts.next(), 2)?;
+        if parts.next().is_some() {
+            return Err(DateError::InvalidFormat);
+        }
+        Self::new(year, month, day)
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DateOfBirth(Date);
+
+impl DateOfBirth {
+    pub fn date(&self) -> Date {
+        self.0
+    }
+
+    /// How old someone born on this date is today.
+    pub fn get_age(&self) -> u8 {
+        self.age(&SystemClock)
+    }
+
+    /// How old someone born on this date is today, according to `clock`.
+    pub fn age<C: Clock>(&self, clock: &C) -> u8 {
+        self.age_on(clock.today())
+    }
+
+    /// How old someone born on this date is on `today`.
+    ///
+    /// Anyone born on 29 February has their birthday on 1 March in years
+    /// without one, and anyone not born yet is 0.
+    pub fn age_on(&self, today: Date) -> u8 {
+        let mut age = today.year - self.0.year;
+        if (today.month, today.day) < (self.0.month, self.0.day) {
+            age -= 1;
+        }
+        age.clamp(0, u8::MAX.into()) as u8
+    }
+}
+
+impl FromStr for DateOfBirth {
+    type Err = DateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Date::from_str(s).map(Self)
+    }
+}
+
+impl fmt::Display for DateOfBirth {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::FixedClock;
+
+    fn date(s: &str) -> Date {
+        Date::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_parse_date() {
+        let leap_day = date("2024-02-29");
+        assert_eq!(
+            (leap_day.year(), leap_day.month(), leap_day.day()),
+            (2024, 2, 29)
+        );
+        assert_eq!(leap_day.to_string(), "2024-02-29");
+        assert!(Date::from_str("2000-02-29").is_ok());
+
+        for (s, error) in [
+            ("1 May 2009", DateError::InvalidFormat),
+            ("2009-5-1", DateError::InvalidFormat),
+            ("2009-05-01-01", DateError::InvalidFormat),
+            ("2009-+5-01", DateError::InvalidFormat),
+            ("2009-13-01", DateError::InvalidMonth(13)),
+            ("2009-00-01", DateError::InvalidMonth(0)),
+            (
+                "2009-04-31",
+                DateError::InvalidDay {
+                    year: 2009,
+                    month: 4,
+                    day: 31,
+                },
+            ),
+            (
+                "2023-02-29",
+                DateError::InvalidDay {
+                    year: 2023,
+                    month: 2,
+                    day: 29,
+                },
+            ),
+            (
+                "1900-02-29",
+                DateError::InvalidDay {
+                    year: 1900,
+                    month: 2,
+                    day: 29,
+                },
+            ),
+        ] {
+            assert_eq!(Date::from_str(s), Err(error), "{s}");
+        }
+    }
+
+    #[test]
+    fn test_from_days_since_epoch() {
+        assert_eq!(Date::from_days_since_epoch(0), date("1970-01-01"));
+        assert_eq!(Date::from_days_since_epoch(-1), date("1969-12-31"));
+        assert_eq!(Date::from_days_since_epoch(11_016), date("2000-02-29"));
+        assert_eq!(Date::from_days_since_epoch(19_723), date("2024-01-01"));
+
+        for s in ["1970-01-01", "1969-12-31", "2000-02-29", "0001-01-01", "9999-12-31"] {
+            let date = date(s);
+            assert_eq!(Date::from_days_since_epoch(date.days_since_epoch()), date);
+        }
+        assert_eq!(date("2024-01-01").days_since_epoch(), 19_723);
+    }
+
+    #[test]
+    fn test_age_on() {
+        let yuki = DateOfBirth::from_str("2009-05-01").unwrap();
+        assert_eq!(yuki.age_on(date("2030-04-30")), 20);
+        assert_eq!(yuki.age_on(date("2030-05-01")), 21);
+        assert_eq!(yuki.age_on(date("2009-05-01")), 0);
+        assert_eq!(yuki.age_on(date("2000-01-01")), 0);
+
+        let leapling = DateOfBirth::from_str("2004-02-29").unwrap();
+        assert_eq!(leapling.age_on(date("2025-02-28")), 20);
+        assert_eq!(leapling.age_on(date("2025-03-01")), 21);
+        assert_eq!(leapling.age_on(date("2028-02-29")), 24);
+
+        let clock = FixedClock::new(date("2030-05-01"));
+        assert_eq!(yuki.age(&clock), 21);
+
+        let ancient = DateOfBirth::from_str("1000-01-01").unwrap();
+        assert_eq!(ancient.age_on(date("2025-01-01")), u8::MAX);
+    }
+}
@@ -0,0 +1,480 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+use unicode_normalization::UnicodeNormalization;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum EmailAddressError {
+    MissingAt,
+    TooLong {
+        length: usize,
+    },
+    EmptyLocalPart,
+    LocalPartTooLong {
+        length: usize,
+    },
+    /// The local part starts or ends with a dot, or has two in a row.
+    MisplacedDot,
+    InvalidLocalPartCharacter(char),
+    UnterminatedQuote,
+    /// Something other than the domain follows a quoted local part.
+    TextAfterQuote,
+    EmptyDomain,
+    DomainTooLong {
+        length: usize,
+    },
+    /// The domain starts or ends with a dot, or has two in a row.
+    EmptyLabel,
+    LabelTooLong {
+        label: String,
+        length: usize,
+    },
+    LabelHyphen {
+        label: String,
+    },
+    /// The label is too long for its punycode encoding to be worked out.
+    InvalidPunycode {
+        label: String,
+    },
+    InvalidDomainCharacter(char),
+}
+
+impl fmt::Display for EmailAddressError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::MissingAt => write!(f, "Email address must contain an @"),
+            Self::TooLong { length } => write!(
+                f,
+                "Email address must be at most {} bytes, not {length}",
+                EmailAddress::MAX_LENGTH
+            ),
+            Self::EmptyLocalPart => write!(f, "Email address has nothing before the @"),
+            Self::LocalPartTooLong { length } => write!(
+                f,
+                "The part before the @ must be at most {} bytes, not {length}",
+                EmailAddress::MAX_LOCAL_PART_LENGTH
+            ),
+            Self::MisplacedDot => write!(
+                f,
+                "The part before the @ can't start or end with a dot, or have two in a row"
+            ),
+            Self::InvalidLocalPartCharacter(c) => {
+                write!(f, "The part before the @ can't contain {c:?} unless quoted")
+            }
+            Self::UnterminatedQuote => write!(f, "Quoted part before the @ is never closed"),
+            Self::TextAfterQuote => write!(f, "Only the @ can follow a quoted part"),
+            Self::EmptyDomain => write!(f, "Email address has nothing after the @"),
+            Self::DomainTooLong { length } => write!(
+                f,
+                "Domain must be at most {} bytes, not {length}",
+                EmailAddress::MAX_DOMAIN_LENGTH
+            ),
+            Self::EmptyLabel => write!(
+                f,
+                "Domain can't start or end with a dot, or have two in a row"
+            ),
+            Self::LabelTooLong { label, length } => write!(
+                f,
+                "Domain label {label:?} must be at most {} bytes, not {length}",
+                EmailAddress::MAX_LABEL_LENGTH
+            ),
+            Self::LabelHyphen { label } => {
+                write!(f, "Domain label {label:?} can't start or end with a hyphen")
+            }
+            Self::InvalidPunycode { label } => {
+                write!(f, "Domain label {label:?} can't be encoded as punycode")
+            }
+            Self::InvalidDomainCharacter(c) => write!(f, "Domain can't contain {c:?}"),
+        }
+    }
+}
+
+impl Error for EmailAddressError {}
+
+/// An email address, checked against the rules of RFC 5322 that matter in
+/// practice.
+///
+/// The local part (before the `@`) is either dot separated atoms like
+/// `daniel.mason+spam` or a quoted string like `"daniel mason"`. The domain
+/// is dot separated labels of letters, digits and hyphens. Non-ASCII letters
+/// are allowed in both, with internationalized domains checked in their
+/// ASCII (punycode) form. Comments, folding whitespace and IP address domains
+/// aren't supported.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct EmailAddress {
+    address: String,
+    at: usize,
+}
+
+impl EmailAddress {
+    pub const MAX_LENGTH: usize = 254;
+    pub const MAX_LOCAL_PART_LENGTH: usize = 64;
+    pub const MAX_DOMAIN_LENGTH: usize = 253;
+    pub const MAX_LABEL_LENGTH: usize = 63;
+
+    /// Builds the address `email!` has already checked while compiling,
+    /// with its `@` at byte `at`. Everything else should parse it instead.
+    #[doc(hidden)]
+    pub fn from_validated_unchecked(address: &str, at: usize) -> Self {
+        Self {
+            address: address.to_string(),
+            at,
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.address
+    }
+
+    /// Everything before the `@`, including the quotes if it's quoted.
+    pub fn local_part(&self) -> &str {
+        &self.address[..self.at]
+    }
+
+    /// Everything after the `@`, as written.
+    pub fn domain(&self) -> &str {
+        &self.address[self.at + 1..]
+    }
+
+    /// The domain as DNS sees it, with internationalized labels encoded as
+    /// punycode, eg `xn--mnchen-3ya.de` for `münchen.de`.
+    pub fn ascii_domain(&self) -> String {
+        self.domain()
+            .split('.')
+            .map(|label| ascii_label(label).expect("labels are checked when parsed"))
+            .collect::<Vec<_>>()
+            .join(".")
+    }
+}
+
+fn is_atext(c: char) -> bool {
+    c.is_ascii_alphanumeric()
+        || "!#$%&'*+-/=?^_`{|}~".contains(c)
+        || !c.is_ascii() && c.is_alphanumeric()
+}
+
+/// Checks a quoted local part, returning the length of it including the
+/// quotes.
+fn quoted_local_part(address: &str) -> Result<usize, EmailAddressError> {
+    let mut chars = address.char_indices().skip(1);
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '"' => return Ok(index + 1),
+            '\\' => match chars.next() {
+                Some((_, c)) if c == ' ' || c.is_ascii_graphic() => {}
+                Some((_, c)) => return Err(EmailAddressError::InvalidLocalPartCharacter(c)),
+                None => return Err(EmailAddressError::UnterminatedQuote),
+            },
+            c if c == ' ' || c.is_ascii_graphic() || !c.is_ascii() && !c.is_control() => {}
+            c => return Err(EmailAddressError::InvalidLocalPartCharacter(c)),
+        }
+    }
+    Err(EmailAddressError::UnterminatedQuote)
+}
+
+fn check_dot_atom(local_part: &str) -> Result<(), EmailAddressError> {
+    if let Some(c) = local_part.chars().find(|&c| c != '.' && !is_atext(c)) {
+        return Err(EmailAddressError::InvalidLocalPartCharacter(c));
+    }
+    if local_part.split('.').any(str::is_empty) {
+        return Err(EmailAddressError::MisplacedDot);
+    }
+    Ok(())
+}
+
+/// The ASCII form of a domain label, or `None` if it can't be encoded.
+fn ascii_label(label: &str) -> Option<String> {
+    if label.is_ascii() {
+        return Some(label.to_string());
+    }
+    let label: String = label.nfc().flat_map(char::to_lowercase).collect();
+    Some(format!("xn--{}", punycode(&label)?))
+}
+
+fn check_domain(domain: &str) -> Result<(), EmailAddressError> {
+    if domain.is_empty() {
+        return Err(EmailAddressError::EmptyDomain);
+    }
+
+    let mut length = 0;
+    for label in domain.split('.') {
+        if label.is_empty() {
+            return Err(EmailAddressError::EmptyLabel);
+        }
+        if let Some(c) = label.chars().find(|&c| !(c.is_alphanumeric() || c == '-')) {
+            return Err(EmailAddressError::InvalidDomainCharacter(c));
+        }
+        if label.starts_with('-') || label.ends_with('-') {
+            return Err(EmailAddressError::LabelHyphen {
+                label: label.to_string(),
+            });
+        }
+        let ascii = ascii_label(label).ok_or_else(|| EmailAddressError::InvalidPunycode {
+            label: label.to_string(),
+        })?;
+        if ascii.len() > EmailAddress::MAX_LABEL_LENGTH {
+            return Err(EmailAddressError::LabelTooLong {
+                label: label.to_string(),
+                length: ascii.len(),
+            });
+        }
+        length += ascii.len() + 1;
+    }
+
+    // Don't count the last label's dot, it doesn't have one
+    let length = length - 1;
+    if length > EmailAddress::MAX_DOMAIN_LENGTH {
+        return Err(EmailAddressError::DomainTooLong { length });
+    }
+    Ok(())
+}
+
+impl FromStr for EmailAddress {
+    type Err = EmailAddressError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let address: String = s.nfc().collect();
+        if address.len() > Self::MAX_LENGTH {
+            return Err(EmailAddressError::TooLong {
+                length: address.len(),
+            });
+        }
+
+        let at = if address.starts_with('"') {
+            let end = quoted_local_part(&address)?;
+            match address[end..].chars().next() {
+                Some('@') => end,
+                Some(_) => return Err(EmailAddressError::TextAfterQuote),
+                None => return Err(EmailAddressError::MissingAt),
+            }
+        } else {
+            let at = address.find('@').ok_or(EmailAddressError::MissingAt)?;
+            if at == 0 {
+                return Err(EmailAddressError::EmptyLocalPart);
+            }
+            check_dot_atom(&address[..at])?;
+            at
+        };
+
+        if at > Self::MAX_LOCAL_PART_LENGTH {
+            return Err(EmailAddressError::LocalPartTooLong { length: at });
+        }
+        check_domain(&address[at + 1..])?;
+
+        Ok(Self { address, at })
+    }
+}
+
+impl fmt::Debug for EmailAddress {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("EmailAddress").field(&self.address).finish()
+    }
+}
+
+impl fmt::Display for EmailAddress {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.address)
+    }
+}
+
+/// Encodes `input` as punycode (RFC 3492), without the `xn--` prefix.
+/// Returns `None` if it's too long to encode.
+fn punycode(input: &str) -> Option<String> {
+    const BASE: u32 = 36;
+    const T_MIN: u32 = 1;
+    const T_MAX: u32 = 26;
+
+    fn digit(d: u32) -> char {
+        match d {
+            0..26 => (b'a' + d as u8) as char,
+            _ => (b'0' + (d - 26) as u8) as char,
+        }
+    }
+
+    fn adapt(delta: u32, points: u32, first: bool) -> u32 {
+        let mut delta = if first { delta / 700 } else { delta / 2 };
+        delta += delta / points;
+        let mut k = 0;
+        while delta > ((BASE - T_MIN) * T_MAX) / 2 {
+            delta /= BASE - T_MIN;
+            k += BASE;
+        }
+        k + (BASE - T_MIN + 1) * delta / (delta + 38)
+    }
+
+    let code_points: Vec<u32> = input.chars().map(u32::from).collect();
+    let mut output: String = input.chars().filter(char::is_ascii).collect();
+    let basic = output.len() as u32;
+    if basic > 0 {
+        output.push('-');
+    }
+
+    let mut n = 128;
+    let mut delta: u32 = 0;
+    let mut bias = 72;
+    let mut handled = basic;
+    while (handled as usize) < code_points.len() {
+        let next = *code_points.iter().filter(|&&c| c >= n).min()?;
+        delta = delta.checked_add((next - n).checked_mul(handled + 1)?)?;
+        n = next;
+        for &c in &code_points {
+            if c < n {
+                delta = delta.checked_add(1)?;
+            }
+            if c == n {
+                let mut q = delta;
+                let mut k = BASE;
+                loop {
+                    let t = k.saturating_sub(bias).clamp(T_MIN, T_MAX);
+                    if q < t {
+                        break;
+                    }
+                    output.push(digit(t + (q - t) % (BASE - t)));
+                    q = (q - t) / (BASE - t);
+                    k += BASE;
+                }
+                output.push(digit(q));
+                bias = adapt(delta, handled + 1, handled == basic);
+                delta = 0;
+                handled += 1;
+            }
+        }
+        delta += 1;
+        n += 1;
+    }
+    Some(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(address: &str) -> Result<EmailAddress, EmailAddressError> {
+        EmailAddress::from_str(address)
+    }
+
+    #[test]
+    fn test_valid_email_addresses() {
+        for (address, local_part, domain) in [
+            ("daniel@example.com", "daniel", "example.com"),
+            ("a@b", "a", "b"),
+            (
+                "daniel.mason+spam@mail.example.co.uk",
+                "daniel.mason+spam",
+                "mail.example.co.uk",
+            ),
+            ("o'brien@example.com", "o'brien", "example.com"),
+            (
+                "\"daniel mason\"@example.com",
+                "\"daniel mason\"",
+                "example.com",
+            ),
+            (
+                "\"very.(),:;<>[]\\\".unusual@\"@example.com",
+                "\"very.(),:;<>[]\\\".unusual@\"",
+                "example.com",
+            ),
+            ("zoë@example.com", "zoë", "example.com"),
+            ("daniel@münchen.de", "daniel", "münchen.de"),
+            ("daniel@xn--mnchen-3ya.de", "daniel", "xn--mnchen-3ya.de"),
+        ] {
+            let email = parse(address).unwrap();
+            assert_eq!(email.as_str(), address);
+            assert_eq!(email.local_part(), local_part);
+            assert_eq!(email.domain(), domain);
+        }
+    }
+
+    #[test]
+    fn test_invalid_email_addresses() {
+        use EmailAddressError::*;
+
+        for (address, error) in [
+            ("daniel", MissingAt),
+            ("@example.com", EmptyLocalPart),
+            ("daniel@", EmptyDomain),
+            (".daniel@example.com", MisplacedDot),
+            ("daniel.@example.com", MisplacedDot),
+            ("dan..iel@example.com", MisplacedDot),
+            ("dan iel@example.com", InvalidLocalPartCharacter(' ')),
+            ("dan\"iel@example.com", InvalidLocalPartCharacter('"')),
+            ("dan@iel@example.com", InvalidDomainCharacter('@')),
+            ("\"daniel@example.com", UnterminatedQuote),
+            ("\"dan\"iel@example.com", TextAfterQuote),
+            ("\"daniel\"", MissingAt),
+            ("daniel@.example.com", EmptyLabel),
+            ("daniel@example..com", EmptyLabel),
+            ("daniel@example.com.", EmptyLabel),
+            ("daniel@exa_mple.com", InvalidDomainCharacter('_')),
+            ("daniel@[127.0.0.1]", InvalidDomainCharacter('[')),
+            (
+                "daniel@-example.com",
+                LabelHyphen {
+                    label: "-example".to_string(),
+                },
+            ),
+        ] {
+            assert_eq!(parse(address), Err(error), "{address}");
+        }
+    }
+
+    #[test]
+    fn test_length_limits() {
+        let local_part = "a".repeat(64);
+        assert!(parse(&format!("{local_part}@example.com")).is_ok());
+        assert_eq!(
+            parse(&format!("{local_part}a@example.com")),
+            Err(EmailAddressError::LocalPartTooLong { length: 65 })
+        );
+
+        let label = "a".repeat(63);
+        assert!(parse(&format!("daniel@{label}.com")).is_ok());
+        assert_eq!(
+            parse(&format!("daniel@{label}a.com")),
+            Err(EmailAddressError::LabelTooLong {
+                label: format!("{label}a"),
+                length: 64
+            })
+        );
+
+        // Four 62 byte labels and their dots come to 251 bytes, with a
+        // three byte local part that's too long overall
+        let domain = vec!["a".repeat(62); 4].join(".");
+        assert_eq!(
+            parse(&format!("dan@{domain}")),
+            Err(EmailAddressError::TooLong { length: 255 })
+        );
+        assert!(parse(&format!("da@{domain}")).is_ok());
+    }
+
+    #[test]
+    fn test_internationalized_domains() {
+        let email = parse("daniel@Bücher.example").unwrap();
+        assert_eq!(email.ascii_domain(), "xn--bcher-kva.example");
+        assert_eq!(
+            parse("daniel@münchen.de").unwrap().ascii_domain(),
+            "xn--mnchen-3ya.de"
+        );
+        assert_eq!(
+            parse("daniel@例え.テスト").unwrap().ascii_domain(),
+            "xn--r8jz45g.xn--zckzah"
+        );
+
+        // Labels are measured once encoded, which adds the xn-- prefix and a
+        // hyphen to the 58 ASCII letters and the ü
+        let label = format!("{}ü", "a".repeat(58));
+        assert!(matches!(
+            parse(&format!("daniel@{label}.de")),
+            Err(EmailAddressError::LabelTooLong { length: 66, .. })
+        ));
+
+        // Far too long to be a label, but long enough to overflow punycode
+        // rather than be reported with a made up length
+        let label = format!("{}𠀀", "a".repeat(40_000));
+        assert_eq!(
+            check_domain(&label),
+            Err(EmailAddressError::InvalidPunycode { label })
+        );
+    }
+}
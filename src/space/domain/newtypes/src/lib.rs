@@ -0,0 +1,198 @@
+//! The domain types every example shares: [`Username`], [`EmailAddress`]
+//! and [`DateOfBirth`], each validated when it's created, so holding one
+//! means it's valid.
+//!
+//! They're all `Debug`, `Clone`, `PartialEq`, `Eq` and `Hash`, and display
+//! as the string they were parsed from. The `serde` feature serializes them
+//! as those strings too, validating them again when they're deserialized.
+
+use newtype_derive::Newtype;
+use std::error::Error;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use unicode_normalization::UnicodeNormalization;
+
+mod clock;
+mod date_of_birth;
+mod email_address;
+#[cfg(feature = "serde")]
+mod serde_impls;
+
+pub use clock::{Clock, FixedClock, SystemClock};
+pub use date_of_birth::{Date, DateError, DateOfBirth};
+pub use email_address::{EmailAddress, EmailAddressError};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum UsernameError {
+    TooShort { length: usize },
+    TooLong { length: usize },
+    SurroundingWhitespace,
+    InvalidCharacter(char),
+}
+
+impl fmt::Display for UsernameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::TooShort { length } => write!(
+                f,
+                "Username must be at least {} characters, not {length}",
+                Username::MIN_LENGTH
+            ),
+            Self::TooLong { length } => write!(
+                f,
+                "Username must be at most {} characters, not {length}",
+                Username::MAX_LENGTH
+            ),
+            Self::SurroundingWhitespace => {
+                write!(f, "Username can't start or end with whitespace")
+            }
+            Self::InvalidCharacter(c) => write!(f, "Username can't contain {c:?}"),
+        }
+    }
+}
+
+impl Error for UsernameError {}
+
+/// A username: letters and digits from any script, plus `_`, `-`, `.` and
+/// spaces between words.
+///
+/// Usernames are stored in Unicode NFC form, so the same name typed two
+/// different ways is the same username, and compare case-insensitively, so
+/// "Daniel" and "daniel" can't both sign up.
+#[derive(Debug, Clone, Newtype)]
+#[newtype(validate = Username::validate, error = UsernameError)]
+pub struct Username(String);
+
+impl Username {
+    pub const MIN_LENGTH: usize = 3;
+    pub const MAX_LENGTH: usize = 32;
+
+    /// Builds the username `username!` has already checked and normalized
+    /// while compiling. Everything else should parse it instead.
+    #[doc(hidden)]
+    pub fn from_validated_unchecked(username: &str) -> Self {
+        Self(username.to_string())
+    }
+
+    /// The characters usernames are compared by.
+    fn folded(&self) -> impl Iterator<Item = char> + '_ {
+        self.0.chars().flat_map(char::to_lowercase)
+    }
+
+    /// The username as it is compared, for storing somewhere that can only
+    /// compare exactly, like a database column or a map key. Two usernames
+    /// are equal exactly when their keys are.
+    pub fn key(&self) -> String {
+        self.folded().collect()
+    }
+
+    fn validate(username: String) -> Result<String, UsernameError> {
+        let username: String = username.nfc().collect();
+
+        if username.trim() != username {
+            return Err(UsernameError::SurroundingWhitespace);
+        }
+        let length = username.chars().count();
+        if length < Self::MIN_LENGTH {
+            return Err(UsernameError::TooShort { length });
+        }
+        if length > Self::MAX_LENGTH {
+            return Err(UsernameError::TooLong { length });
+        }
+        let allowed = |c: char| c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | ' ');
+        if let Some(c) = username.chars().find(|&c| !allowed(c)) {
+            return Err(UsernameError::InvalidCharacter(c));
+        }
+
+        Ok(username)
+    }
+}
+
+impl PartialEq for Username {
+    fn eq(&self, other: &Self) -> bool {
+        self.folded().eq(other.folded())
+    }
+}
+
+impl Eq for Username {}
+
+impl Hash for Username {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.folded().for_each(|c| c.hash(state));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_valid_usernames() {
+        for username in ["Daniel", "yuki_1", "Ted-E. Bear", "Zoë", "ダニエル"] {
+            assert_eq!(Username::from_str(username).unwrap().as_str(), username);
+        }
+    }
+
+    #[test]
+    fn test_invalid_usernames() {
+        assert_eq!(
+            Username::from_str("Al"),
+            Err(UsernameError::TooShort { length: 2 })
+        );
+        assert_eq!(
+            Username::from_str(""),
+            Err(UsernameError::TooShort { length: 0 })
+        );
+        assert_eq!(
+            Username::from_str(&"a".repeat(33)),
+            Err(UsernameError::TooLong { length: 33 })
+        );
+        assert_eq!(
+            Username::from_str(" Daniel"),
+            Err(UsernameError::SurroundingWhitespace)
+        );
+        assert_eq!(
+            Username::from_str("Daniel\n"),
+            Err(UsernameError::SurroundingWhitespace)
+        );
+        assert_eq!(
+            Username::from_str("dan@example.com"),
+            Err(UsernameError::InvalidCharacter('@'))
+        );
+        assert_eq!(
+            Username::from_str("Dan\tiel"),
+            Err(UsernameError::InvalidCharacter('\t'))
+        );
+    }
+
+    #[test]
+    fn test_usernames_are_normalized() {
+        // "e" followed by a combining acute accent is composed into "é"
+        let decomposed = Username::from_str("Rene\u{301}e").unwrap();
+        assert_eq!(decomposed.as_str(), "Ren\u{e9}e");
+        assert_eq!(decomposed, Username::from_str("Ren\u{e9}e").unwrap());
+        // The accent doesn't count towards the length once it's composed
+        assert!(Username::from_str("Zo\u{eb}").is_ok());
+        assert!(Username::from_str("Zoe\u{308}").is_ok());
+    }
+
+    #[test]
+    fn test_usernames_compare_case_insensitively() {
+        let daniel = Username::from_str("Daniel").unwrap();
+        assert_eq!(daniel, Username::from_str("dAnIeL").unwrap());
+        assert_ne!(daniel, Username::from_str("Danielle").unwrap());
+        // Case is kept for display
+        assert_eq!(daniel.to_string(), "Daniel");
+        assert_eq!(daniel.key(), "daniel");
+        assert_eq!(daniel.key(), Username::from_str("DANIEL").unwrap().key());
+
+        let usernames: HashSet<_> = ["Daniel", "DANIEL", "Yuki"]
+            .into_iter()
+            .map(|username| Username::from_str(username).unwrap())
+            .collect();
+        assert_eq!(usernames.len(), 2);
+    }
+}
@@ -0,0 +1,121 @@
+//! Serializes each newtype as the string it's parsed from, and parses it
+//! with the same `FromStr` on the way back in, so anything deserialized has
+//! been validated like anything else.
+
+use serde::de::{self, Deserialize, Deserializer, Visitor};
+use serde::ser::{Serialize, Serializer};
+use std::fmt;
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+use crate::{Date, DateOfBirth, EmailAddress, Username};
+
+struct FromStrVisitor<T> {
+    expecting: &'static str,
+    output: PhantomData<T>,
+}
+
+impl<T> Visitor<'_> for FromStrVisitor<T>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    type Value = T;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.expecting)
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<T, E> {
+        value.parse().map_err(E::custom)
+    }
+}
+
+macro_rules! serde_as_string {
+    ($($newtype:ty => $expecting:literal),* $(,)?) => {$(
+        impl Serialize for $newtype {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_str(self)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $newtype {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                deserializer.deserialize_str(FromStrVisitor {
+                    expecting: $expecting,
+                    output: PhantomData,
+                })
+            }
+        }
+    )*};
+}
+
+serde_as_string! {
+    Username => "a username",
+    EmailAddress => "an email address",
+    Date => "a date written YYYY-MM-DD",
+    DateOfBirth => "a date of birth written YYYY-MM-DD",
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialize_as_strings() {
+        let username = Username::from_str("Daniel").unwrap();
+        let email = EmailAddress::from_str("daniel@example.com").unwrap();
+        let date_of_birth = DateOfBirth::from_str("2004-02-29").unwrap();
+
+        assert_eq!(serde_json::to_string(&username).unwrap(), r#""Daniel""#);
+        assert_eq!(
+            serde_json::to_string(&email).unwrap(),
+            r#""daniel@example.com""#
+        );
+        assert_eq!(
+            serde_json::to_string(&date_of_birth).unwrap(),
+            r#""2004-02-29""#
+        );
+    }
+
+    #[test]
+    fn test_deserialize_round_trips() {
+        let username: Username = serde_json::from_str(r#""Daniel""#).unwrap();
+        assert_eq!(username.as_str(), "Daniel");
+        let email: EmailAddress = serde_json::from_str(r#""daniel@example.com""#).unwrap();
+        assert_eq!(email.domain(), "example.com");
+        let date: Date = serde_json::from_str(r#""2004-02-29""#).unwrap();
+        assert_eq!(date, Date::new(2004, 2, 29).unwrap());
+    }
+
+    #[test]
+    fn test_deserialize_validates() {
+        let error = serde_json::from_str::<Username>(r#""Al""#).unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .starts_with("Username must be at least 3 characters, not 2")
+        );
+
+        let error = serde_json::from_str::<EmailAddress>(r#""daniel""#).unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .starts_with("Email address must contain an @")
+        );
+
+        let error = serde_json::from_str::<DateOfBirth>(r#""2023-02-29""#).unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .starts_with("There is no day 29 in 2023-02")
+        );
+
+        let error = serde_json::from_str::<Username>("42").unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .starts_with("invalid type: integer `42`, expected a username")
+        );
+    }
+}
@@ -1,12 +1,11 @@
 use std::str::FromStr;
 
-mod newtype;
-use newtype::*;
+use newtypes::*;
 
 #[derive(Debug)]
 struct User {
     username: Username,
-    email: Option<Email>,
+    email: Option<EmailAddress>,
     date_of_birth: Option<DateOfBirth>,
 }
 
@@ -19,7 +18,7 @@ impl User {
         }
     }
 
-    fn with_email(mut self, email: Email) -> Self {
+    fn with_email(mut self, email: EmailAddress) -> Self {
         self.email = Some(email);
         self
     }
@@ -35,11 +34,10 @@ impl User {
 fn main() -> anyhow::Result<()> {
     let yuki = User::new(Username::from_str("Yuki")?);
 
-    let yuki = yuki.with_email(Email::from_str("yuki@example.com")?);
+    let yuki = yuki.with_email(EmailAddress::from_str("yuki@example.com")?);
     let yuki = yuki.with_date_of_birth(DateOfBirth::from_str("2009-05-01")?);
 
     dbg!(yuki);
 
     Ok(())
 }
-
@@ -1,12 +1,15 @@
+use newtypes::*;
 use std::str::FromStr;
+use thiserror::Error;
 
-mod newtype;
-use newtype::*;
+#[derive(Debug, Error)]
+#[error("User is too young")]
+struct TooYoung;
 
 #[derive(Debug)]
 struct User {
     username: Username,
-    email: Option<Email>,
+    email: Option<EmailAddress>,
     date_of_birth: Option<DateOfBirth>,
 }
 
@@ -19,13 +22,17 @@ impl User {
         }
     }
 
-    fn with_email(mut self, email: Email) -> Self {
+    fn with_email(mut self, email: EmailAddress) -> Self {
         self.email = Some(email);
         self
     }
 
-    fn with_date_of_birth(mut self, date_of_birth: DateOfBirth) -> Result<Self, TooYoung> {
-        if date_of_birth.years_old() < 21 {
+    fn with_date_of_birth<C: Clock>(
+        mut self,
+        date_of_birth: DateOfBirth,
+        clock: &C,
+    ) -> Result<Self, TooYoung> {
+        if date_of_birth.age(clock) < 21 {
             return Err(TooYoung);
         }
         self.date_of_birth = Some(date_of_birth);
@@ -37,8 +44,8 @@ impl User {
 
 fn main() -> anyhow::Result<()> {
     let yuki = User::new(Username::from_str("Yuki")?)
-        .with_date_of_birth(DateOfBirth::from_str("2009-05-01")?)?
-        .with_email(Email::from_str("yuki@example.com")?);
+        .with_date_of_birth(DateOfBirth::from_str("2009-05-01")?, &SystemClock)?
+        .with_email(EmailAddress::from_str("yuki@example.com")?);
 
     dbg!(yuki);
 
@@ -1,13 +1,11 @@
 use std::str::FromStr;
 
-mod newtype;
-use newtype::*;
+use newtypes::*;
 
 #[derive(Debug)]
 struct User {
-
     username: Username,
-    email: Option<Email>,
+    email: Option<EmailAddress>,
     date_of_birth: Option<DateOfBirth>,
 }
 
@@ -20,7 +18,7 @@ impl User {
         }
     }
 
-    fn set_email(&mut self, email: Email) -> &mut Self {
+    fn set_email(&mut self, email: EmailAddress) -> &mut Self {
         self.email = Some(email);
         self
     }
@@ -36,7 +34,7 @@ impl User {
 fn main() -> anyhow::Result<()> {
     let mut yuki = User::new(Username::from_str("Yuki")?);
 
-    yuki.set_email(Email::from_str("yuki@example.com")?)
+    yuki.set_email(EmailAddress::from_str("yuki@example.com")?)
         .set_date_of_birth(DateOfBirth::from_str("2009-05-01")?);
 
     dbg!(yuki);
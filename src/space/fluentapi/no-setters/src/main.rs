@@ -1,7 +1,6 @@
 use std::str::FromStr;
 
-mod newtype;
-use newtype::*;
+use newtypes::*;
 
 #[derive(Debug)]
 struct User {
@@ -9,7 +8,7 @@ struct User {
     username: Username,
 
     // We want to be able to change these fields
-    pub email: Option<Email>,
+    pub email: Option<EmailAddress>,
     pub date_of_birth: Option<DateOfBirth>,
 }
 
@@ -28,10 +27,9 @@ impl User {
 fn main() -> anyhow::Result<()> {
     let mut yuki = User::new(Username::from_str("Yuki")?);
 
-    yuki.email = Some(Email::from_str("yuki@example.com")?);
+    yuki.email = Some(EmailAddress::from_str("yuki@example.com")?);
     yuki.date_of_birth = Some(DateOfBirth::from_str("2009-05-01")?);
 
     dbg!(yuki);
     Ok(())
 }
-
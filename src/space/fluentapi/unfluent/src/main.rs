@@ -1,13 +1,12 @@
 use std::str::FromStr;
 
-mod newtype;
-use newtype::*;
+use newtypes::*;
 
 #[derive(Debug)]
 struct User {
     // Direct access to these properties is forbidden
     username: Username,
-    email: Option<Email>,
+    email: Option<EmailAddress>,
     date_of_birth: Option<DateOfBirth>,
 }
 
@@ -20,7 +19,7 @@ impl User {
         }
     }
 
-    fn set_email(&mut self, email: Email) {
+    fn set_email(&mut self, email: EmailAddress) {
         self.email = Some(email)
     }
 
@@ -34,7 +33,7 @@ impl User {
 fn main() -> anyhow::Result<()> {
     let mut yuki = User::new(Username::from_str("Yuki")?);
 
-    yuki.set_email(Email::from_str("yuki@example.com")?);
+    yuki.set_email(EmailAddress::from_str("yuki@example.com")?);
     yuki.set_date_of_birth(DateOfBirth::from_str("2009-05-01")?);
 
     dbg!(yuki);
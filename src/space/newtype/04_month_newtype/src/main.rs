@@ -14,20 +14,20 @@ mod month {
         }
     }
 
-    pub fn get_english_month_name(month: Month) -> String {
+    pub fn get_english_month_name(month: Month) -> &'static str {
         match month.0 {
-            1 => "January".to_string(),
-            2 => "February".to_string(),
-            3 => "March".to_string(),
-            4 => "April".to_string(),
-            5 => "May".to_string(),
-            6 => "June".to_string(),
-            7 => "July".to_string(),
-            8 => "August".to_string(),
-            9 => "September".to_string(),
-            10 => "October".to_string(),
-            11 => "November".to_string(),
-            12 => "December".to_string(),
+            1 => "January",
+            2 => "February",
+            3 => "March",
+            4 => "April",
+            5 => "May",
+            6 => "June",
+            7 => "July",
+            8 => "August",
+            9 => "September",
+            10 => "October",
+            11 => "November",
+            12 => "December",
             _ => panic!("Month with an invalid number should not be possible"),
         }
     }
@@ -0,0 +1,37 @@
+use enum_newtype::date::Date;
+use enum_newtype::locale::Locale;
+use enum_newtype::month::*;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let month = Month::November;
+    println!("{}", get_english_month_name(month));
+    for locale in Locale::ALL {
+        println!(
+            "{locale:?}: {} ({})",
+            month.name(locale),
+            month.short_name(locale)
+        );
+    }
+
+    let month: Month = "févr.".parse()?;
+    println!("{}", month.name(Locale::English));
+
+    let date: Date = "2025-11-28".parse()?;
+    let next_week = date.add_days(7)?;
+    println!(
+        "{date} is a {:?}, a week later is {next_week}",
+        date.weekday()
+    );
+
+    for month in Month::iter() {
+        println!(
+            "{:>2} {month} is in Q{}, after {} and before {}",
+            u64::from(month),
+            month.quarter(),
+            month.previous(),
+            month.next()
+        );
+    }
+
+    Ok(())
+}
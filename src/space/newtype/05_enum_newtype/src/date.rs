@@ -0,0 +1,349 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::month::Month;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DateError {
+    /// Not a four digit year, two digit month and two digit day separated
+    /// by dashes.
+    InvalidFormat,
+    YearOutOfRange(u64),
+    InvalidMonth(u64),
+    DayOutOfRange(u64),
+    /// The day exists, just not in this month, like 30 February.
+    NoSuchDay {
+        year: Year,
+        month: Month,
+        day: Day,
+    },
+    /// Arithmetic went past the first or last year we support.
+    OutOfRange,
+}
+
+impl fmt::Display for DateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidFormat => write!(f, "Expected an ISO 8601 date like 2025-09-24"),
+            Self::YearOutOfRange(year) => write!(
+                f,
+                "Year must be from {} to {}, not {year}",
+                Year::MIN,
+                Year::MAX
+            ),
+            Self::InvalidMonth(month) => write!(f, "There is no month {month}"),
+            Self::DayOutOfRange(day) => write!(f, "Day must be from 1 to 31, not {day}"),
+            Self::NoSuchDay { year, month, day } => {
+                write!(f, "There is no {month} {day} in {year}")
+            }
+            Self::OutOfRange => write!(f, "Date must be between {} and {}", Date::MIN, Date::MAX),
+        }
+    }
+}
+
+impl Error for DateError {}
+
+/// A year from 1 to 9999, which is all four digit ISO 8601 dates can hold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Year(u16);
+
+impl Year {
+    pub const MIN: Year = Year(1);
+    pub const MAX: Year = Year(9999);
+
+    pub fn new(year: u16) -> Result<Self, DateError> {
+        if !(Self::MIN.0..=Self::MAX.0).contains(&year) {
+            return Err(DateError::YearOutOfRange(year.into()));
+        }
+        Ok(Self(year))
+    }
+
+    pub fn get(self) -> u16 {
+        self.0
+    }
+
+    pub fn is_leap(self) -> bool {
+        newtypes::Date::is_leap_year(self.0.into())
+    }
+}
+
+impl fmt::Display for Year {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:04}", self.0)
+    }
+}
+
+/// A day of the month, from 1 to 31. Whether the month has that day is up
+/// to [`Date`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Day(u8);
+
+impl Day {
+    pub fn new(day: u8) -> Result<Self, DateError> {
+        if !(1..=31).contains(&day) {
+            return Err(DateError::DayOutOfRange(day.into()));
+        }
+        Ok(Self(day))
+    }
+
+    pub fn get(self) -> u8 {
+        self.0
+    }
+}
+
+impl fmt::Display for Day {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Weekday {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+/// A day in the Gregorian calendar. Dates order chronologically.
+///
+/// This is [`newtypes::Date`] with its numbers swapped for newtypes, and it
+/// converts to that one to parse and count days.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    // The field order is what makes the derived ordering chronological
+    year: Year,
+    month: Month,
+    day: Day,
+}
+
+impl Date {
+    pub const MIN: Date = Date {
+        year: Year::MIN,
+        month: Month::January,
+        day: Day(1),
+    };
+    pub const MAX: Date = Date {
+        year: Year::MAX,
+        month: Month::December,
+        day: Day(31),
+    };
+
+    pub fn new(year: Year, month: Month, day: Day) -> Result<Self, DateError> {
+        if day.0 > month.days_in(year) {
+            return Err(DateError::NoSuchDay { year, month, day });
+        }
+        Ok(Self { year, month, day })
+    }
+
+    pub fn year(self) -> Year {
+        self.year
+    }
+
+    pub fn month(self) -> Month {
+        self.month
+    }
+
+    pub fn day(self) -> Day {
+        self.day
+    }
+
+    pub fn weekday(self) -> Weekday {
+        use Weekday::*;
+        // Day 0, 1970-01-01, was a Thursday
+        let weekdays = [
+            Thursday, Friday, Saturday, Sunday, Monday, Tuesday, Wednesday,
+        ];
+        weekdays[self.days_since_epoch().rem_euclid(7) as usize]
+    }
+
+    /// The date `days` days later, or earlier if `days` is negative.
+    pub fn add_days(self, days: i64) -> Result<Self, DateError> {
+        let days = self
+            .days_since_epoch()
+            .checked_add(days)
+            .ok_or(DateError::OutOfRange)?;
+        if !(Self::MIN.days_since_epoch()..=Self::MAX.days_since_epoch()).contains(&days) {
+            return Err(DateError::OutOfRange);
+        }
+        Ok(Self::from_days_since_epoch(days))
+    }
+
+    /// How many days it is from this date to `other`, negative if `other`
+    /// is earlier.
+    pub fn days_until(self, other: Date) -> i64 {
+        other.days_since_epoch() - self.days_since_epoch()
+    }
+
+    fn days_since_epoch(self) -> i64 {
+        newtypes::Date::from(self).days_since_epoch()
+    }
+
+    /// Only called with days in range.
+    fn from_days_since_epoch(days: i64) -> Self {
+        let date = newtypes::Date::from_days_since_epoch(days);
+        Self {
+            year: Year(date.year() as u16),
+            month: Month::try_from(date.month()).expect("a Date's month is from 1 to 12"),
+            day: Day(date.day()),
+        }
+    }
+}
+
+impl From<Date> for newtypes::Date {
+    fn from(date: Date) -> Self {
+        newtypes::Date::new(date.year.0.into(), u64::from(date.month) as u8, date.day.0)
+            .expect("a Date is always a valid newtypes::Date")
+    }
+}
+
+impl FromStr for Date {
+    type Err = DateError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (year, month, day) = match s.parse::<newtypes::Date>() {
+            Ok(date) => (date.year(), date.month(), date.day()),
+            // Checked again below, to say which part is out of range
+            Err(newtypes::DateError::InvalidDay { year, month, day }) => (year, month, day),
+            Err(newtypes::DateError::InvalidMonth(month)) => {
+                return Err(DateError::InvalidMonth(month.into()));
+            }
+            Err(_) => return Err(DateError::InvalidFormat),
+        };
+
+        // Four digit years always fit
+        let year = Year::new(year as u16)?;
+        let month = Month::try_from(month).map_err(|_| DateError::InvalidMonth(month.into()))?;
+        let day = Day::new(day)?;
+        Self::new(year, month, day)
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}-{:02}-{:02}",
+            self.year,
+            u64::from(self.month),
+            self.day.0
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> Date {
+        Date::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_new_rejects_impossible_dates() {
+        let year = |year| Year::new(year).unwrap();
+        let day = |day| Day::new(day).unwrap();
+
+        assert!(Date::new(year(2024), Month::February, day(29)).is_ok());
+        assert_eq!(
+            Date::new(year(2025), Month::February, day(29)),
+            Err(DateError::NoSuchDay {
+                year: year(2025),
+                month: Month::February,
+                day: day(29)
+            })
+        );
+        assert!(Date::new(year(2000), Month::February, day(29)).is_ok());
+        assert!(Date::new(year(1900), Month::February, day(29)).is_err());
+        assert!(Date::new(year(2025), Month::February, day(30)).is_err());
+        assert!(Date::new(year(2025), Month::April, day(31)).is_err());
+        assert!(Date::new(year(2025), Month::May, day(31)).is_ok());
+
+        assert_eq!(Year::new(0), Err(DateError::YearOutOfRange(0)));
+        assert_eq!(Year::new(10_000), Err(DateError::YearOutOfRange(10_000)));
+        assert_eq!(Day::new(0), Err(DateError::DayOutOfRange(0)));
+        assert_eq!(Day::new(32), Err(DateError::DayOutOfRange(32)));
+    }
+
+    #[test]
+    fn test_iso_8601() {
+        let date = date("2025-09-24");
+        assert_eq!(date.year().get(), 2025);
+        assert_eq!(date.month(), Month::September);
+        assert_eq!(date.day().get(), 24);
+        assert_eq!(date.to_string(), "2025-09-24");
+        assert_eq!(Date::MIN.to_string(), "0001-01-01");
+
+        for (s, error) in [
+            ("24/09/2025", DateError::InvalidFormat),
+            ("2025-9-24", DateError::InvalidFormat),
+            ("2025-09-24T00:00", DateError::InvalidFormat),
+            ("2025-09-24-01", DateError::InvalidFormat),
+            ("0000-01-01", DateError::YearOutOfRange(0)),
+            ("2025-13-01", DateError::InvalidMonth(13)),
+            ("2025-00-01", DateError::InvalidMonth(0)),
+            ("2025-01-32", DateError::DayOutOfRange(32)),
+        ] {
+            assert_eq!(Date::from_str(s), Err(error), "{s}");
+        }
+        assert!(matches!(
+            Date::from_str("2025-02-29"),
+            Err(DateError::NoSuchDay { .. })
+        ));
+    }
+
+    #[test]
+    fn test_add_days() {
+        assert_eq!(date("2025-09-24").add_days(7), Ok(date("2025-10-01")));
+        assert_eq!(date("2025-12-31").add_days(1), Ok(date("2026-01-01")));
+        assert_eq!(date("2024-02-28").add_days(1), Ok(date("2024-02-29")));
+        assert_eq!(date("2025-02-28").add_days(1), Ok(date("2025-03-01")));
+        assert_eq!(date("2025-03-01").add_days(-1), Ok(date("2025-02-28")));
+        assert_eq!(date("2025-01-01").add_days(365), Ok(date("2026-01-01")));
+        assert_eq!(date("2024-01-01").add_days(366), Ok(date("2025-01-01")));
+
+        assert_eq!(Date::MAX.add_days(1), Err(DateError::OutOfRange));
+        assert_eq!(Date::MIN.add_days(-1), Err(DateError::OutOfRange));
+        assert_eq!(Date::MIN.add_days(i64::MAX), Err(DateError::OutOfRange));
+
+        assert_eq!(date("2025-01-01").days_until(date("2026-01-01")), 365);
+        assert_eq!(date("2026-01-01").days_until(date("2025-01-01")), -365);
+        assert_eq!(
+            Date::MIN.add_days(Date::MIN.days_until(Date::MAX)),
+            Ok(Date::MAX)
+        );
+    }
+
+    #[test]
+    fn test_weekday() {
+        assert_eq!(date("1970-01-01").weekday(), Weekday::Thursday);
+        assert_eq!(date("2025-09-24").weekday(), Weekday::Wednesday);
+        assert_eq!(date("2000-02-29").weekday(), Weekday::Tuesday);
+        assert_eq!(date("0001-01-01").weekday(), Weekday::Monday);
+        assert_eq!(date("9999-12-31").weekday(), Weekday::Friday);
+    }
+
+    #[test]
+    fn test_ordering() {
+        let mut dates = vec![
+            date("2025-09-24"),
+            date("2024-12-31"),
+            date("2025-10-01"),
+            date("2025-09-03"),
+        ];
+        dates.sort();
+        assert_eq!(
+            dates,
+            [
+                date("2024-12-31"),
+                date("2025-09-03"),
+                date("2025-09-24"),
+                date("2025-10-01"),
+            ]
+        );
+    }
+}
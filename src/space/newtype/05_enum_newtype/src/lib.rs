@@ -0,0 +1,10 @@
+//! The `Month` enum from `main.rs`, grown into a small calendar with
+//! localized names and a `Date` made of `Year`, `Month` and `Day` newtypes.
+//! `main.rs` stays the slide's minimal example; `examples/calendar.rs` shows
+//! the rest in use.
+
+pub mod date;
+pub mod locale;
+pub mod month;
+#[cfg(feature = "serde")]
+mod serde_impls;
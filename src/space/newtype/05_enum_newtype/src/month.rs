@@ -0,0 +1,298 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::date::Year;
+use crate::locale::Locale;
+
+/// A month of the year, numbered from 1 for January.
+///
+/// With the `serde` feature it serializes as its English name, and
+/// deserializes from anything [`Month::from_str`] parses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(u64)]
+pub enum Month {
+    January = 1,
+    February = 2,
+    March = 3,
+    April = 4,
+    May = 5,
+    June = 6,
+    July = 7,
+    August = 8,
+    September = 9,
+    October = 10,
+    November = 11,
+    December = 12,
+}
+
+const MONTHS: [Month; 12] = [
+    Month::January,
+    Month::February,
+    Month::March,
+    Month::April,
+    Month::May,
+    Month::June,
+    Month::July,
+    Month::August,
+    Month::September,
+    Month::October,
+    Month::November,
+    Month::December,
+];
+
+impl Month {
+    pub fn name(self, locale: Locale) -> &'static str {
+        locale.long_month_names()[self.index()]
+    }
+
+    pub fn short_name(self, locale: Locale) -> &'static str {
+        locale.short_month_names()[self.index()]
+    }
+
+    /// The months from January to December.
+    pub fn iter() -> impl DoubleEndedIterator<Item = Month> + ExactSizeIterator {
+        MONTHS.into_iter()
+    }
+
+    /// The month after this one, January after December.
+    pub fn next(self) -> Month {
+        MONTHS[(self.index() + 1) % 12]
+    }
+
+    /// The month before this one, December before January.
+    pub fn previous(self) -> Month {
+        MONTHS[(self.index() + 11) % 12]
+    }
+
+    pub fn days_in(self, year: Year) -> u8 {
+        newtypes::Date::days_in_month(year.get().into(), self as u8)
+    }
+
+    /// Which quarter of the year the month falls in, from 1 to 4.
+    pub fn quarter(self) -> u8 {
+        self.index() as u8 / 3 + 1
+    }
+
+    fn index(self) -> usize {
+        self as usize - 1
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidMonthNumber(u64);
+
+impl fmt::Display for InvalidMonthNumber {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Month number must be from 1 to 12, not {}", self.0)
+    }
+}
+
+impl Error for InvalidMonthNumber {}
+
+impl TryFrom<u64> for Month {
+    type Error = InvalidMonthNumber;
+
+    fn try_from(month: u64) -> Result<Self, Self::Error> {
+        usize::try_from(month)
+            .ok()
+            .and_then(|month| MONTHS.get(month.checked_sub(1)?))
+            .copied()
+            .ok_or(InvalidMonthNumber(month))
+    }
+}
+
+impl TryFrom<u8> for Month {
+    type Error = InvalidMonthNumber;
+
+    fn try_from(month: u8) -> Result<Self, Self::Error> {
+        Month::try_from(u64::from(month))
+    }
+}
+
+impl From<Month> for u64 {
+    fn from(month: Month) -> Self {
+        month as u64
+    }
+}
+
+/// Writes the English name, see [`Month::name`] for other languages.
+impl fmt::Display for Month {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name(Locale::English))
+    }
+}
+
+pub fn get_english_month_name(month: Month) -> &'static str {
+    month.name(Locale::English)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseMonthError(String);
+
+impl fmt::Display for ParseMonthError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "\"{}\" is not the name of a month", self.0)
+    }
+}
+
+impl Error for ParseMonthError {}
+
+impl FromStr for Month {
+    type Err = ParseMonthError;
+
+    /// Parses the number of a month, or its long or short name in any
+    /// [`Locale`], ignoring case and whether the abbreviation ends with a dot.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        fn normalize(name: &str) -> String {
+            name.trim_end_matches('.').to_lowercase()
+        }
+
+        if let Ok(month) = s.trim().parse::<u64>() {
+            return Month::try_from(month).map_err(|_| ParseMonthError(s.to_string()));
+        }
+
+        let name = normalize(s.trim());
+        Locale::ALL
+            .iter()
+            .flat_map(|locale| [locale.long_month_names(), locale.short_month_names()])
+            .find_map(|names| {
+                let index = names.iter().position(|n| normalize(n) == name)?;
+                Some(MONTHS[index])
+            })
+            .ok_or_else(|| ParseMonthError(s.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_names() {
+        assert_eq!(Month::March.name(Locale::English), "March");
+        assert_eq!(Month::March.short_name(Locale::English), "Mar");
+        assert_eq!(Month::February.name(Locale::French), "février");
+        assert_eq!(Month::February.short_name(Locale::French), "févr.");
+        assert_eq!(Month::March.name(Locale::German), "März");
+        assert_eq!(Month::October.short_name(Locale::German), "Okt.");
+        assert_eq!(Month::January.name(Locale::Spanish), "enero");
+        assert_eq!(Month::June.short_name(Locale::Italian), "giu");
+        assert_eq!(get_english_month_name(Month::November), "November");
+    }
+
+    #[test]
+    fn test_parse_every_name() {
+        for locale in Locale::ALL {
+            for month in Month::iter() {
+                for name in [month.name(locale), month.short_name(locale)] {
+                    assert_eq!(name.parse(), Ok(month), "{name} in {locale:?}");
+                    assert_eq!(
+                        name.to_uppercase().parse(),
+                        Ok(month),
+                        "{name} in {locale:?}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!("sept".parse(), Ok(Month::September));
+        assert_eq!("Sept.".parse(), Ok(Month::September));
+        assert_eq!(" août ".parse(), Ok(Month::August));
+        assert_eq!("jan.".parse(), Ok(Month::January));
+        assert_eq!(
+            "Smarch".parse::<Month>(),
+            Err(ParseMonthError("Smarch".to_string()))
+        );
+        assert!("".parse::<Month>().is_err());
+        assert_eq!("9".parse(), Ok(Month::September));
+        assert_eq!("09".parse(), Ok(Month::September));
+        assert!("0".parse::<Month>().is_err());
+        assert!("13".parse::<Month>().is_err());
+        assert!("-1".parse::<Month>().is_err());
+    }
+
+    #[test]
+    fn test_numbers() {
+        for number in 0..=u8::MAX {
+            let month = Month::try_from(number);
+            assert_eq!(month, Month::try_from(u64::from(number)));
+            match month {
+                Ok(month) => assert_eq!(u64::from(month), u64::from(number)),
+                Err(error) => {
+                    assert!(number == 0 || number > 12);
+                    assert_eq!(error, InvalidMonthNumber(number.into()));
+                }
+            }
+        }
+        assert_eq!(Month::try_from(u64::MAX), Err(InvalidMonthNumber(u64::MAX)));
+        assert_eq!(
+            InvalidMonthNumber(13).to_string(),
+            "Month number must be from 1 to 12, not 13"
+        );
+    }
+
+    #[test]
+    fn test_iter() {
+        let months: Vec<_> = Month::iter().collect();
+        assert_eq!(months, MONTHS);
+        assert_eq!(Month::iter().len(), 12);
+        assert!(months.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(Month::iter().next_back(), Some(Month::December));
+    }
+
+    #[test]
+    fn test_next_and_previous() {
+        for month in Month::iter() {
+            assert_eq!(month.next().previous(), month);
+            assert_eq!(month.previous().next(), month);
+            if month != Month::December {
+                assert_eq!(u64::from(month.next()), u64::from(month) + 1);
+            }
+        }
+        assert_eq!(Month::December.next(), Month::January);
+        assert_eq!(Month::January.previous(), Month::December);
+
+        let mut month = Month::March;
+        for _ in 0..12 {
+            month = month.next();
+        }
+        assert_eq!(month, Month::March);
+    }
+
+    #[test]
+    fn test_days_in() {
+        let days_in_year = |year| {
+            let year = Year::new(year).unwrap();
+            Month::iter()
+                .map(|month| u32::from(month.days_in(year)))
+                .sum::<u32>()
+        };
+        assert_eq!(days_in_year(2025), 365);
+        assert_eq!(days_in_year(2024), 366);
+        assert_eq!(days_in_year(2000), 366);
+        assert_eq!(days_in_year(1900), 365);
+
+        let year = Year::new(2025).unwrap();
+        let days: Vec<_> = Month::iter().map(|month| month.days_in(year)).collect();
+        assert_eq!(days, [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31]);
+    }
+
+    #[test]
+    fn test_quarter() {
+        let quarters: Vec<_> = Month::iter().map(Month::quarter).collect();
+        assert_eq!(quarters, [1, 1, 1, 2, 2, 2, 3, 3, 3, 4, 4, 4]);
+    }
+
+    #[test]
+    fn test_display_round_trips() {
+        for month in Month::iter() {
+            assert_eq!(month.to_string().parse(), Ok(month));
+            assert_eq!(u64::from(month).to_string().parse(), Ok(month));
+        }
+        assert_eq!(Month::August.to_string(), "August");
+    }
+}
@@ -0,0 +1,68 @@
+//! Serializes a [`Month`] as its English name, and parses it back with its
+//! `FromStr`, so any name or number it accepts deserializes too.
+
+use serde::de::{self, Deserialize, Deserializer, Visitor};
+use serde::ser::{Serialize, Serializer};
+use std::fmt;
+
+use crate::month::Month;
+
+impl Serialize for Month {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+struct MonthVisitor;
+
+impl Visitor<'_> for MonthVisitor {
+    type Value = Month;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("the name of a month")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Month, E> {
+        value.parse().map_err(E::custom)
+    }
+}
+
+impl<'de> Deserialize<'de> for Month {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_str(MonthVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialize_as_name() {
+        assert_eq!(
+            serde_json::to_string(&Month::November).unwrap(),
+            r#""November""#
+        );
+    }
+
+    #[test]
+    fn test_deserialize() {
+        for json in [r#""November""#, r#""nov.""#, r#""novembre""#, r#""11""#] {
+            let month: Month = serde_json::from_str(json).unwrap();
+            assert_eq!(month, Month::November, "{json}");
+        }
+
+        let error = serde_json::from_str::<Month>(r#""Smarch""#).unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .starts_with("\"Smarch\" is not the name of a month")
+        );
+        let error = serde_json::from_str::<Month>("11").unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .starts_with("invalid type: integer `11`, expected the name of a month")
+        );
+    }
+}
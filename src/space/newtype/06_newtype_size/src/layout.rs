@@ -0,0 +1,393 @@
+use std::any::type_name;
+use std::fmt::{self, Write};
+use std::marker::PhantomData;
+use std::ops::Range;
+
+/// Describes a field of a type given to a [`Layout`].
+///
+/// The layout of Rust types isn't something the compiler tells us, so the
+/// fields have to be listed by hand, most easily with the [`layout!`] macro.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Field {
+    pub name: &'static str,
+    pub type_name: &'static str,
+    pub offset: usize,
+    pub size: usize,
+}
+
+impl Field {
+    pub fn bytes(&self) -> Range<usize> {
+        self.offset..self.offset + self.size
+    }
+}
+
+/// The size, alignment and fields of `T`, and whether wrapping it in an
+/// [`Option`] costs anything.
+pub struct Layout<T> {
+    size: usize,
+    align: usize,
+    option_size: usize,
+    fields: Vec<Field>,
+    // Only describes a T, never holds one
+    of: PhantomData<fn() -> T>,
+}
+
+/// Builds a [`Layout`] of a struct with its fields, working out the offsets
+/// with [`std::mem::offset_of!`].
+///
+/// ```
+/// use newtype_size::layout;
+///
+/// struct Meters(f64);
+///
+/// let layout = layout!(Meters { 0: f64 });
+/// assert!(layout.is_same_as(&layout!(f64)));
+/// ```
+#[macro_export]
+macro_rules! layout {
+    ($type:ty) => {
+        $crate::layout::Layout::<$type>::of()
+    };
+    ($type:ty { $($field:tt: $field_type:ty),* $(,)? }) => {
+        $crate::layout::Layout::<$type>::of()
+            $(.with_field::<$field_type>(
+                stringify!($field),
+                ::std::mem::offset_of!($type, $field),
+            ))*
+    };
+}
+
+// Written out rather than derived, which would ask the same of T
+impl<T> fmt::Debug for Layout<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Layout")
+            .field("type_name", &self.type_name())
+            .field("size", &self.size)
+            .field("align", &self.align)
+            .field("option_size", &self.option_size)
+            .field("fields", &self.fields)
+            .finish()
+    }
+}
+
+impl<T> Clone for Layout<T> {
+    fn clone(&self) -> Self {
+        Self {
+            fields: self.fields.clone(),
+            ..*self
+        }
+    }
+}
+
+impl<T> PartialEq for Layout<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.is_same_as(other) && self.fields == other.fields
+    }
+}
+
+impl<T> Eq for Layout<T> {}
+
+impl<T> Layout<T> {
+    pub fn of() -> Self {
+        Self {
+            size: size_of::<T>(),
+            align: align_of::<T>(),
+            option_size: size_of::<Option<T>>(),
+            fields: Vec::new(),
+            of: PhantomData,
+        }
+    }
+
+    /// Adds a field of type `F` starting `offset` bytes into the type.
+    ///
+    /// # Panics
+    ///
+    /// If the field doesn't fit in the type or overlaps another field.
+    pub fn with_field<F>(mut self, name: &'static str, offset: usize) -> Self {
+        let field = Field {
+            name,
+            type_name: type_name::<F>(),
+            offset,
+            size: size_of::<F>(),
+        };
+        assert!(
+            field.bytes().end <= self.size,
+            "Field {name} doesn't fit in {}",
+            self.type_name()
+        );
+        assert!(
+            self.fields
+                .iter()
+                .all(|other| field.bytes().end <= other.offset
+                    || other.bytes().end <= field.offset),
+            "Field {name} overlaps another field of {}",
+            self.type_name()
+        );
+        self.fields.push(field);
+        self.fields.sort_by_key(|field| field.offset);
+        self
+    }
+
+    pub fn type_name(&self) -> &'static str {
+        type_name::<T>()
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn align(&self) -> usize {
+        self.align
+    }
+
+    /// The fields listed so far, in the order they are laid out in memory.
+    pub fn fields(&self) -> &[Field] {
+        &self.fields
+    }
+
+    pub fn option_size(&self) -> usize {
+        self.option_size
+    }
+
+    /// Whether the type has a niche, some bit pattern it can never hold,
+    /// that the compiler can use to store `None` in, making `Option<T>` free.
+    pub fn has_niche(&self) -> bool {
+        self.option_size == self.size
+    }
+
+    /// The bytes not covered by any of the listed fields. Without listed
+    /// fields every byte counts as data.
+    pub fn padding(&self) -> Vec<Range<usize>> {
+        if self.fields.is_empty() {
+            return Vec::new();
+        }
+        let mut padding = Vec::new();
+        let mut start = 0;
+        for field in &self.fields {
+            if start < field.offset {
+                padding.push(start..field.offset);
+            }
+            start = field.bytes().end;
+        }
+        if start < self.size {
+            padding.push(start..self.size);
+        }
+        padding
+    }
+
+    pub fn padding_bytes(&self) -> usize {
+        self.padding().iter().map(ExactSizeIterator::len).sum()
+    }
+
+    /// Whether the two types cost the same to store: the same size and
+    /// alignment, and the same cost when wrapped in an [`Option`].
+    pub fn is_same_as<U>(&self, other: &Layout<U>) -> bool {
+        self.size == other.size
+            && self.align == other.align
+            && self.option_size == other.option_size
+    }
+
+    /// Each region of the type, field or padding, in memory order.
+    fn regions(&self) -> Vec<(Range<usize>, String)> {
+        if self.fields.is_empty() {
+            return vec![(0..self.size, "value".to_string())];
+        }
+        let mut regions: Vec<_> = self
+            .fields
+            .iter()
+            .map(|field| {
+                (
+                    field.bytes(),
+                    format!("{}: {}", field.name, field.type_name),
+                )
+            })
+            .chain(
+                self.padding()
+                    .into_iter()
+                    .map(|bytes| (bytes, "padding".to_string())),
+            )
+            .collect();
+        regions.sort_by_key(|(bytes, _)| bytes.start);
+        regions
+    }
+
+    /// Shows the bytes of `value` in hex next to the field they belong to.
+    /// Padding bytes may be uninitialised, so they are never read and show
+    /// up as `--`.
+    ///
+    /// # Safety
+    ///
+    /// Every byte that is read has to be initialised. That's every byte of
+    /// the listed fields, so they have to be where they were said to be and
+    /// have no padding of their own, or every byte of `T` if there are no
+    /// listed fields, which rules out `T` having padding or being an enum
+    /// like `Option<u32>` whose variants don't fill all of it. [`layout!`]
+    /// gets the offsets right, but can't check the rest.
+    pub unsafe fn annotate(&self, value: &T) -> String {
+        let pointer = value as *const T as *const u8;
+        let mut output = format!("{}\n", self.type_name());
+        for (bytes, label) in self.regions() {
+            let hex: Vec<_> = if label == "padding" {
+                bytes.clone().map(|_| "--".to_string()).collect()
+            } else {
+                bytes
+                    .clone()
+                    .map(|offset| {
+                        // SAFETY: offset is inside T, and is part of a field
+                        // rather than padding, which the caller promises
+                        // has been initialised.
+                        let byte = unsafe { *pointer.add(offset) };
+                        format!("{byte:02x}")
+                    })
+                    .collect()
+            };
+            writeln!(
+                output,
+                "  {:>4}..{:<4} {label:<24} {}",
+                bytes.start,
+                bytes.end,
+                hex.join(" ")
+            )
+            .expect("Writing to a String can't fail");
+        }
+        output
+    }
+}
+
+impl<T> fmt::Display for Layout<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "{}: {} bytes, aligned to {}",
+            self.type_name(),
+            self.size,
+            self.align
+        )?;
+        if self.has_niche() {
+            writeln!(f, "  Option: {} bytes, uses a niche", self.option_size)?;
+        } else {
+            writeln!(
+                f,
+                "  Option: {} bytes, {} more for the tag",
+                self.option_size,
+                self.option_size - self.size
+            )?;
+        }
+        for (bytes, label) in self.regions() {
+            writeln!(f, "  {:>4}..{:<4} {label}", bytes.start, bytes.end)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroU32;
+
+    use super::*;
+
+    #[allow(dead_code)]
+    struct Padded {
+        small: u8,
+        large: u32,
+        medium: u16,
+    }
+
+    #[allow(dead_code)]
+    #[repr(C)]
+    struct PaddedC {
+        small: u8,
+        large: u32,
+        medium: u16,
+    }
+
+    #[test]
+    fn test_fields_and_padding() {
+        let layout = layout!(PaddedC {
+            small: u8,
+            large: u32,
+            medium: u16
+        });
+        assert_eq!(layout.size(), 12);
+        assert_eq!(layout.align(), 4);
+        assert_eq!(
+            layout.fields().iter().map(|f| f.offset).collect::<Vec<_>>(),
+            [0, 4, 8]
+        );
+        assert_eq!(layout.padding(), [1..4, 10..12]);
+        assert_eq!(layout.padding_bytes(), 5);
+
+        // Rust is free to reorder fields, and does so to save space, though
+        // exactly how isn't promised
+        assert!(layout!(Padded).size() < layout.size());
+
+        assert!(layout!(u64).padding().is_empty());
+    }
+
+    #[test]
+    fn test_niche() {
+        assert!(!layout!(u32).has_niche());
+        assert_eq!(layout!(u32).option_size(), 8);
+        assert!(layout!(NonZeroU32).has_niche());
+        assert!(layout!(bool).has_niche());
+        assert!(layout!(&u8).has_niche());
+    }
+
+    #[test]
+    fn test_annotate() {
+        let value = PaddedC {
+            small: 1,
+            large: 0x0403_0201,
+            medium: 0xbeef,
+        };
+        let layout = layout!(PaddedC {
+            small: u8,
+            large: u32,
+            medium: u16
+        });
+        // SAFETY: the fields are listed with layout! and are integers, so
+        // have no padding of their own
+        let annotated = unsafe { layout.annotate(&value) };
+        let lines: Vec<_> = annotated
+            .lines()
+            .map(str::split_whitespace)
+            .map(Iterator::collect::<Vec<_>>)
+            .collect();
+        assert_eq!(lines[0], [type_name::<PaddedC>()]);
+        assert_eq!(lines[1], ["0..1", "small:", "u8", "01"]);
+        assert_eq!(lines[2], ["1..4", "padding", "--", "--", "--"]);
+        assert_eq!(lines[3], ["4..8", "large:", "u32", "01", "02", "03", "04"]);
+        assert_eq!(lines[4], ["8..10", "medium:", "u16", "ef", "be"]);
+        assert_eq!(lines[5], ["10..12", "padding", "--", "--"]);
+
+        // SAFETY: a u16 has no padding
+        let annotated = unsafe { layout!(u16).annotate(&0x1234u16) };
+        assert!(annotated.ends_with("0..2    value                    34 12\n"));
+    }
+
+    #[test]
+    #[should_panic(expected = "overlaps another field")]
+    fn test_overlapping_fields() {
+        let _ = Layout::<u64>::of()
+            .with_field::<u32>("low", 0)
+            .with_field::<u32>("middle", 2);
+    }
+
+    #[test]
+    fn test_display() {
+        let layout = layout!(PaddedC {
+            small: u8,
+            large: u32,
+            medium: u16
+        });
+        let display = layout.to_string();
+        assert!(display.contains("12 bytes, aligned to 4"));
+        assert!(display.contains("Option: 16 bytes, 4 more for the tag"));
+        assert!(display.contains("1..4    padding"));
+        assert!(
+            layout!(bool)
+                .to_string()
+                .contains("Option: 1 bytes, uses a niche")
+        );
+    }
+}
@@ -1,5 +1,9 @@
+// Only read through get_memory, which the compiler can't see
+#[allow(dead_code)]
 struct MonthStruct(u64);
 
+// Only September is used, the other months are there to show the enum
+#[allow(dead_code)]
 #[repr(u64)]
 enum MonthEnum {
     January = 1,
@@ -16,9 +20,16 @@ enum MonthEnum {
     December = 12,
 }
 
-fn get_memory<T>(input: &T) -> &[u8] {
+/// Shows the raw bytes of a value.
+///
+/// # Safety
+///
+/// Every byte of `input` has to be initialised, so `T` can't have padding.
+/// `newtype_size::layout::Layout::annotate` can show types that do.
+unsafe fn get_memory<T>(input: &T) -> &[u8] {
     // Credit: https://bennett.dev/rust/dump-struct-bytes/
-    // SAFETY: Size of slice being read is the sizeof T which must be known (not ?Sized)
+    // SAFETY: Size of slice being read is the sizeof T which must be known
+    // (not ?Sized), and the caller promises every byte is initialised
     unsafe { std::slice::from_raw_parts(input as *const _ as *const u8, size_of::<T>()) }
 }
 
@@ -27,9 +38,14 @@ fn main() {
     let sept_struct = MonthStruct(9);
     let sept_enum = MonthEnum::September;
 
-    let num_bytes = get_memory(&sept_num);
-    let struct_bytes = get_memory(&sept_struct);
-    let enum_bytes = get_memory(&sept_enum);
+    // SAFETY: all three are a single u64, with no padding
+    let (num_bytes, struct_bytes, enum_bytes) = unsafe {
+        (
+            get_memory(&sept_num),
+            get_memory(&sept_struct),
+            get_memory(&sept_enum),
+        )
+    };
 
     assert_eq!([9, 0, 0, 0, 0, 0, 0, 0], num_bytes);
     assert_eq!([9, 0, 0, 0, 0, 0, 0, 0], struct_bytes);
@@ -0,0 +1,56 @@
+use enum_newtype::date::{Date, Day, Year};
+use enum_newtype::month::Month;
+use newtype_size::layout;
+use newtype_size::layout::Layout;
+use newtypes::{DateOfBirth, Username};
+
+fn assert_zero_cost<T, U>(newtype: Layout<T>, inner: Layout<U>) {
+    assert!(
+        newtype.is_same_as(&inner),
+        "{newtype}costs more than\n{inner}"
+    );
+}
+
+#[test]
+fn test_struct_newtypes_cost_nothing() {
+    assert_zero_cost(layout!(Year), layout!(u16));
+    assert_zero_cost(layout!(Day), layout!(u8));
+    assert_zero_cost(layout!(Username), layout!(String));
+    assert_zero_cost(layout!(DateOfBirth), layout!(newtypes::Date));
+}
+
+#[test]
+fn test_enum_newtypes_cost_nothing() {
+    let month = layout!(Month);
+    assert_eq!(month.size(), size_of::<u64>());
+    assert_eq!(month.align(), align_of::<u64>());
+
+    // Only 12 of the possible values are months, so unlike u64 an Option of
+    // a Month is free
+    assert!(month.has_niche());
+    assert!(!layout!(u64).has_niche());
+}
+
+#[test]
+fn test_option_of_a_newtype_is_free() {
+    assert!(layout!(Month).has_niche());
+    assert!(layout!(Username).has_niche());
+    assert!(layout!(Date).has_niche());
+}
+
+#[test]
+fn test_date_of_birth_option_needs_a_tag() {
+    // The shared Date stores its month as a plain number, so every bit
+    // pattern is taken and an Option needs a tag
+    assert!(!layout!(DateOfBirth).has_niche());
+}
+
+#[test]
+fn test_date_is_no_bigger_than_its_parts() {
+    let date = layout!(Date);
+    let parts = size_of::<Year>() + size_of::<Month>() + size_of::<Day>();
+    assert!(
+        date.size() <= parts.next_multiple_of(date.align()),
+        "{date}"
+    );
+}
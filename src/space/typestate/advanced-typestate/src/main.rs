@@ -1,114 +1,437 @@
-trait PullRequestState {}
+#[macro_use]
+mod state_machine;
 
-struct Open;
-struct Approved;
-struct Rejected;
-struct Merged;
+use std::collections::BTreeSet;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
 
-impl PullRequestState for Open {}
-impl PullRequestState for Approved {}
-impl PullRequestState for Rejected {}
-impl PullRequestState for Merged {}
+/// The id of a git commit, the hex of its hash, possibly shortened.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct CommitId(String);
 
-struct PullRequest<S: PullRequestState> {
-    state: S,
-    // ...other PR details ...
+#[derive(Debug, PartialEq)]
+struct InvalidCommitId(String);
+
+impl fmt::Display for InvalidCommitId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "\"{}\" is not a commit id, which is 7 to 40 hex digits",
+            self.0
+        )
+    }
 }
 
-impl PullRequest<Open> {
-    fn open() -> Self {
-        Self {
-            state: Open,
+impl Error for InvalidCommitId {}
+
+impl FromStr for CommitId {
+    type Err = InvalidCommitId;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if !(7..=40).contains(&s.len()) || !s.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(InvalidCommitId(s.to_string()));
+        }
+        Ok(Self(s.to_ascii_lowercase()))
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum ReviewError {
+    AuthorCanNotReview,
+    NotAReviewer(String),
+    Closed(&'static str),
+}
+
+impl fmt::Display for ReviewError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::AuthorCanNotReview => write!(f, "The author of a PR can't review it"),
+            Self::NotAReviewer(name) => write!(f, "{name} wasn't asked to review this PR"),
+            Self::Closed(status) => write!(f, "Can't review a PR that is {status}"),
         }
     }
 }
 
+impl Error for ReviewError {}
+
+state_machine! {
+    struct PullRequest<S: PullRequestState> {
+        title: String,
+        author: String,
+        reviewers: BTreeSet<String>,
+        approvals: BTreeSet<String>,
+        required_approvals: usize,
+        commits: Vec<CommitId>,
+    }
+    enum Status { Open, Approved, Rejected, Merged { commit: CommitId } }
+    error InvalidTransition;
+    transitions {
+        approve if has_enough_approvals: Open | Approved -> Approved,
+        reject: Open | Approved -> Rejected,
+        merge: Approved -> Merged { commit: CommitId },
+    }
+}
+
 impl PullRequest<Open> {
-    fn approve(self) -> PullRequest<Approved> {
-        PullRequest::<Approved> {
-            state: Approved,
-            // ... move self into PullRequest<Approved> ...
+    fn open(title: &str, author: &str, required_approvals: usize) -> Self {
+        Self {
+            state: Open,
+            title: title.to_string(),
+            author: author.to_string(),
+            reviewers: BTreeSet::new(),
+            approvals: BTreeSet::new(),
+            required_approvals,
+            commits: Vec::new(),
         }
     }
 
-    fn reject(self) -> PullRequest<Rejected> {
-        PullRequest::<Rejected> {
-            state: Rejected,
-            // ... move self into PullRequest<Approved> ...
+    fn request_review(&mut self, reviewer: &str) -> Result<(), ReviewError> {
+        if reviewer == self.author {
+            return Err(ReviewError::AuthorCanNotReview);
         }
+        self.reviewers.insert(reviewer.to_string());
+        Ok(())
+    }
+
+    /// New commits haven't been reviewed, so they throw away the approvals
+    /// given so far.
+    fn push_commit(&mut self, commit: CommitId) {
+        self.commits.push(commit);
+        self.approvals.clear();
     }
 }
 
-impl PullRequest<Approved> {
-    fn approve(self) -> PullRequest<Approved> {
-        self
+/// The states a PR can still be approved in.
+trait Reviewable: PullRequestState {}
+
+impl Reviewable for Open {}
+impl Reviewable for Approved {}
+
+impl<S: PullRequestState> PullRequest<S> {
+    fn has_enough_approvals(&self) -> bool {
+        self.approvals.len() >= self.required_approvals
     }
 
-    fn reject(self) -> PullRequest<Rejected> {
-        PullRequest::<Rejected> {
-            state: Rejected,
-            // ... move self into PullRequest<Rejected> ...
+    fn record_approval(&mut self, reviewer: &str) -> Result<(), ReviewError> {
+        if !self.reviewers.contains(reviewer) {
+            return Err(ReviewError::NotAReviewer(reviewer.to_string()));
         }
+        // A set, so approving twice still counts once
+        self.approvals.insert(reviewer.to_string());
+        Ok(())
+    }
+}
+
+impl<S: Reviewable> PullRequest<S> {
+    fn add_approval(&mut self, reviewer: &str) -> Result<(), ReviewError> {
+        self.record_approval(reviewer)
     }
+}
 
-    fn merge(self) -> PullRequest<Merged> {
-        PullRequest::<Merged> {
-            state: Merged,
-            // ... move self into PullRequest<Merged> ...
+impl PullRequest<Status> {
+    fn add_approval(&mut self, reviewer: &str) -> Result<(), ReviewError> {
+        match self.state {
+            Status::Open | Status::Approved => self.record_approval(reviewer),
+            _ => Err(ReviewError::Closed(self.state.name())),
         }
     }
 }
 
-fn main() {
-    let open_pr = PullRequest::open();
+impl PullRequest<Merged> {
+    fn merge_commit(&self) -> &CommitId {
+        &self.state.commit
+    }
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let mut open_pr = PullRequest::open("Add typestate examples", "Yuki", 2);
+    open_pr.push_commit("3f2c9e1".parse()?);
+    open_pr.request_review("Daniel")?;
+    open_pr.request_review("Ted")?;
 
     // You can not merge an open PR, the next line won't compile
-    // let merged_pr = open_pr.merge();
+    // let merged_pr = open_pr.merge("9b1d4e7".parse()?);
 
-    // You can approve a PR with Ready for Review or already Approved
-    let approved_pr = open_pr.approve();
-    let still_approved = approved_pr.approve();
+    // Approving needs two different reviewers, so one approval, however many
+    // times it's given, gets us the open PR back
+    open_pr.add_approval("Daniel")?;
+    open_pr.add_approval("Daniel")?;
+    let mut open_pr = match open_pr.approve() {
+        Ok(_) => unreachable!("Daniel's approval only counts once"),
+        Err(open_pr) => open_pr,
+    };
 
-    // Then it can be merged
-    let merged_pr = still_approved.merge();
+    // You can approve a PR with enough approvals, or that's already Approved
+    open_pr.add_approval("Ted")?;
+    let approved_pr = open_pr.approve().map_err(|_| "Not enough approvals")?;
+    let still_approved = approved_pr.approve().map_err(|_| "Not enough approvals")?;
+
+    // Then it can be merged, and knows the commit it was merged as
+    let merged_pr = still_approved.merge("9b1d4e7".parse()?);
+    println!(
+        "{} by {}, {} commit(s), merged as {:?}",
+        merged_pr.title,
+        merged_pr.author,
+        merged_pr.commits.len(),
+        merged_pr.merge_commit()
+    );
 
     // The `.approve()` method doesn't exist for rejected PRs, commented line won't compile
-    let open_pr = PullRequest::open();
-    let rejected_pr = open_pr.reject();
-    // rejected_pr.approve();
+    let open_pr = PullRequest::open("Rewrite it all", "Ted", 1);
+    let _rejected_pr = open_pr.reject();
+    // _rejected_pr.approve();
+
+    // Approved PRs can still be rejected before they are merged
+    let mut open_pr = PullRequest::open("Rename things", "Ted", 0);
+    open_pr.request_review("Yuki")?;
+    let _rejected_pr = open_pr
+        .approve()
+        .map_err(|_| "Not enough approvals")?
+        .reject();
+
+    // The same transitions can be checked at runtime instead, for when we
+    // only find out the state of a PR while the program is running
+    let mut open_pr = PullRequest::open("Fix typo", "Ted", 1);
+    open_pr.request_review("Yuki")?;
+    let mut pr = open_pr.into_status();
+    if let Err(error) = pr.approve() {
+        println!("{error}");
+    }
+    pr.add_approval("Yuki")?;
+    pr.approve()?.merge("c0ffee1".parse()?)?;
+    if let Err(error) = pr.reject() {
+        println!("{error}");
+    }
+    if let Err(error) = pr.add_approval("Yuki") {
+        println!("{error}");
+    }
+
+    // And we can go back to the typestate API once we know the state
+    if let Ok(merged_pr) = pr.into_state::<Merged>() {
+        println!("{:?}", merged_pr.state());
+    }
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn commit(id: &str) -> CommitId {
+        CommitId::from_str(id).unwrap()
+    }
+
+    /// An open PR by Yuki, reviewed by Daniel and Ted, that needs both of
+    /// their approvals.
+    fn open_pr() -> PullRequest<Open> {
+        let mut pr = PullRequest::open("Add typestate examples", "Yuki", 2);
+        pr.push_commit(commit("3f2c9e1"));
+        pr.request_review("Daniel").unwrap();
+        pr.request_review("Ted").unwrap();
+        pr
+    }
+
+    fn approved_pr() -> PullRequest<Approved> {
+        let mut pr = open_pr();
+        pr.add_approval("Daniel").unwrap();
+        pr.add_approval("Ted").unwrap();
+        pr.approve().unwrap()
+    }
+
     #[test]
     fn test_can_approve_open() {
-        let open_pr = PullRequest::open();
-        let _approved_pr = open_pr.approve();
+        let _approved_pr = approved_pr();
     }
 
     #[test]
     fn test_can_approve_approved() {
-        let open_pr = PullRequest::open();
-        let _approved_pr = open_pr.approve().approve();
+        let _approved_pr = approved_pr().approve().unwrap();
     }
 
     #[test]
     fn test_can_reject_open() {
-        let open_pr = PullRequest::open();
-        let _rejected_pr = open_pr.reject();
+        let _rejected_pr = open_pr().reject();
     }
 
     #[test]
     fn test_can_reject_approved() {
-        let open_pr = PullRequest::open();
-        let _rejected_pr = open_pr.approve().reject();
+        let _rejected_pr = approved_pr().reject();
     }
 
     #[test]
     fn test_can_merge_approved() {
-        let open_pr = PullRequest::open();
-        let _merged_pr = open_pr.approve().merge();
+        let merged_pr = approved_pr().merge(commit("9b1d4e7"));
+        assert_eq!(merged_pr.merge_commit(), &commit("9b1d4e7"));
+        assert_eq!(
+            merged_pr.status(),
+            Status::Merged {
+                commit: commit("9b1d4e7")
+            }
+        );
+    }
+
+    #[test]
+    fn test_approving_needs_distinct_reviewers() {
+        let mut pr = open_pr();
+        pr.add_approval("Daniel").unwrap();
+        pr.add_approval("Daniel").unwrap();
+        let mut pr = pr.approve().unwrap_err();
+        assert_eq!(pr.approvals.len(), 1);
+
+        pr.add_approval("Ted").unwrap();
+        assert!(pr.approve().is_ok());
+    }
+
+    #[test]
+    fn test_only_reviewers_approve() {
+        let mut pr = open_pr();
+        assert_eq!(
+            pr.request_review("Yuki"),
+            Err(ReviewError::AuthorCanNotReview)
+        );
+        assert_eq!(
+            pr.add_approval("Yuki"),
+            Err(ReviewError::NotAReviewer("Yuki".to_string()))
+        );
+        assert_eq!(
+            pr.add_approval("Fio"),
+            Err(ReviewError::NotAReviewer("Fio".to_string()))
+        );
+        assert!(pr.approvals.is_empty());
+    }
+
+    #[test]
+    fn test_new_commits_need_approving_again() {
+        let mut pr = open_pr();
+        pr.add_approval("Daniel").unwrap();
+        pr.add_approval("Ted").unwrap();
+        pr.push_commit(commit("a1b2c3d"));
+        assert!(pr.approvals.is_empty());
+        assert!(pr.approve().is_err());
+    }
+
+    #[test]
+    fn test_transitions_keep_the_details() {
+        let merged_pr = approved_pr().merge(commit("9b1d4e7"));
+        assert_eq!(merged_pr.title, "Add typestate examples");
+        assert_eq!(merged_pr.author, "Yuki");
+        assert_eq!(merged_pr.reviewers.len(), 2);
+        assert_eq!(merged_pr.approvals.len(), 2);
+        assert_eq!(merged_pr.required_approvals, 2);
+        assert_eq!(merged_pr.commits, [commit("3f2c9e1")]);
+
+        let rejected_pr = open_pr().reject();
+        assert_eq!(rejected_pr.title, "Add typestate examples");
+        assert_eq!(rejected_pr.commits, [commit("3f2c9e1")]);
+    }
+
+    #[test]
+    fn test_commit_id() {
+        assert_eq!(commit("3F2C9E1"), commit("3f2c9e1"));
+        assert!(CommitId::from_str(&"a".repeat(40)).is_ok());
+        for id in ["3f2c9e", "", "not-a-sha", &"a".repeat(41)] {
+            assert_eq!(CommitId::from_str(id), Err(InvalidCommitId(id.to_string())));
+        }
+    }
+
+    #[test]
+    fn test_runtime_transitions() {
+        let mut pr = open_pr().into_status();
+        assert_eq!(
+            pr.approve().unwrap_err(),
+            InvalidTransition {
+                from: Status::Open,
+                transition: "approve",
+                guard: Some("has_enough_approvals"),
+            }
+        );
+        assert_eq!(
+            pr.merge(commit("9b1d4e7")).unwrap_err().to_string(),
+            "Can't merge a PullRequest that is Open"
+        );
+
+        pr.add_approval("Daniel").unwrap();
+        pr.add_approval("Ted").unwrap();
+        assert!(pr.approve().is_ok());
+        assert!(pr.approve().is_ok());
+        assert!(pr.merge(commit("9b1d4e7")).is_ok());
+        assert_eq!(
+            pr.status(),
+            Status::Merged {
+                commit: commit("9b1d4e7")
+            }
+        );
+
+        let mut pr = open_pr().into_status();
+        assert!(pr.reject().is_ok());
+        assert_eq!(pr.status(), Status::Rejected);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_can_not_leave_rejected_or_merged() {
+        let rejected_pr = open_pr().reject().into_status();
+        let merged_pr = approved_pr().merge(commit("9b1d4e7")).into_status();
+        for mut pr in [rejected_pr, merged_pr] {
+            let status = pr.status();
+            assert!(pr.approve().is_err());
+            assert!(pr.reject().is_err());
+            assert!(pr.merge(commit("c0ffee1")).is_err());
+            assert_eq!(
+                pr.add_approval("Daniel"),
+                Err(ReviewError::Closed(status.name()))
+            );
+            assert_eq!(pr.status(), status);
+        }
+        let mut merged_pr = approved_pr().merge(commit("9b1d4e7")).into_status();
+        assert_eq!(
+            merged_pr.approve().unwrap_err().to_string(),
+            "Can't approve a PullRequest that is Merged"
+        );
+        assert_eq!(
+            open_pr().into_status().approve().unwrap_err().to_string(),
+            "Can't approve a PullRequest that is Open until it has enough approvals"
+        );
+    }
+
+    #[test]
+    fn test_typestate_and_runtime_agree() {
+        // Each typestate transition lands on the status the runtime one does
+        let mut runtime_pr = open_pr().into_status();
+        let open_pr = open_pr();
+        assert_eq!(open_pr.status(), runtime_pr.status());
+
+        let mut open_pr = open_pr.approve().unwrap_err();
+        assert!(runtime_pr.approve().is_err());
+        assert_eq!(open_pr.status(), runtime_pr.status());
+
+        for reviewer in ["Daniel", "Ted"] {
+            open_pr.add_approval(reviewer).unwrap();
+            runtime_pr.add_approval(reviewer).unwrap();
+        }
+        let approved_pr = open_pr.approve().unwrap();
+        runtime_pr.approve().unwrap();
+        assert_eq!(approved_pr.status(), runtime_pr.status());
+
+        let merged_pr = approved_pr.merge(commit("9b1d4e7"));
+        runtime_pr.merge(commit("9b1d4e7")).unwrap();
+        assert_eq!(merged_pr.status(), runtime_pr.status());
+
+        let mut runtime_pr = self::open_pr().into_status();
+        runtime_pr.reject().unwrap();
+        assert_eq!(self::open_pr().reject().status(), runtime_pr.status());
+    }
+
+    #[test]
+    fn test_into_state() {
+        let pr = approved_pr().into_status();
+        let pr = pr.into_state::<Open>().unwrap_err();
+        let pr = pr.into_state::<Approved>().unwrap();
+        let merged_pr: PullRequest<Merged> = pr.merge(commit("9b1d4e7"));
+
+        let pr = merged_pr.into_status();
+        let merged_pr = pr.into_state::<Merged>().unwrap();
+        assert_eq!(merged_pr.merge_commit(), &commit("9b1d4e7"));
+        assert!(merged_pr.into_state::<Rejected>().is_err());
+    }
+}
@@ -0,0 +1,246 @@
+/// Writes a typestate machine and its runtime checked twin from one list of
+/// transitions, so the two can never disagree about what's allowed.
+///
+/// ```ignore
+/// state_machine! {
+///     pub struct PullRequest<S: PullRequestState> {
+///         title: String,
+///         approvals: usize,
+///     }
+///     pub enum Status { Open, Approved, Merged { commit: CommitId } }
+///     error InvalidTransition;
+///     transitions {
+///         approve if has_approvals: Open | Approved -> Approved,
+///         merge: Approved -> Merged { commit: CommitId },
+///     }
+/// }
+/// ```
+///
+/// This declares:
+///
+/// - a struct for each state, holding the fields given to it, and the
+///   `PullRequestState` trait they implement
+/// - `PullRequest<S>` with the given fields plus its `state`
+/// - each transition as a method on `PullRequest<From>` returning
+///   `PullRequest<To>`, so `PullRequest<Open>` has `approve` and no `merge`.
+///   The method takes the fields of the state it moves to, and moves the
+///   other fields of the PR across
+/// - the `Status` enum, with a variant for each state, which is also a
+///   `PullRequestState`. The transitions of `PullRequest<Status>` change the
+///   status in place, or return an `InvalidTransition` error when they aren't
+///   allowed from the current status
+/// - `into_status`, which turns any `PullRequest` into a `PullRequest<Status>`,
+///   for example to keep PRs in different states in one `Vec`, and
+///   `into_state`, which turns it back once we know which state it's in
+///
+/// A transition with `if guard` is only allowed when the `guard(&self) -> bool`
+/// method of the PR, which has to be written by hand, says so. The typestate
+/// method then returns a `Result`, giving back the PR unchanged if it failed.
+/// Guards are named for what they check, `has_approvals` reads as "Can't
+/// approve a PullRequest that is Open until it has approvals".
+///
+/// The fields of states need to be `Debug`, `Clone`, `PartialEq`, `Eq` and
+/// `Hash`, as `Status` is.
+macro_rules! state_machine {
+    (@state $vis:vis $state:ident {}) => {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        $vis struct $state;
+    };
+    (@state $vis:vis $state:ident { $($field:ident: $field_type:ty),+ }) => {
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        $vis struct $state {
+            $(pub $field: $field_type),+
+        }
+    };
+
+    (@typestate $machine:ident, $transition:ident, $guard:tt, [$($from:ident),+], $to:ident, $args:tt) => {
+        $(state_machine!(@method $machine, $transition, $guard, $from, $to, $args);)+
+    };
+    (@method $machine:ident, $transition:ident, [], $from:ident, $to:ident, { $($arg:ident: $arg_type:ty),* }) => {
+        impl $machine<$from> {
+            pub fn $transition(self, $($arg: $arg_type),*) -> $machine<$to> {
+                self.with_state($to { $($arg),* })
+            }
+        }
+    };
+    (@method $machine:ident, $transition:ident, [$guard:ident], $from:ident, $to:ident, { $($arg:ident: $arg_type:ty),* }) => {
+        impl $machine<$from> {
+            #[doc = concat!("Gives back the PR unchanged unless it `", stringify!($guard), "`.")]
+            // Handing the PR back is the point, boxing it would only hide that
+            #[allow(clippy::result_large_err)]
+            pub fn $transition(self, $($arg: $arg_type),*) -> Result<$machine<$to>, Self> {
+                if !self.$guard() {
+                    return Err(self);
+                }
+                Ok(self.with_state($to { $($arg),* }))
+            }
+        }
+    };
+
+    (
+        $vis:vis struct $machine:ident<$s:ident: $state_trait:ident> {
+            $($field_vis:vis $field:ident: $field_type:ty),* $(,)?
+        }
+        $status_vis:vis enum $status:ident {
+            $($state:ident $({ $($state_field:ident: $state_field_type:ty),* $(,)? })?),+ $(,)?
+        }
+        error $error:ident;
+        transitions {
+            $(
+                $transition:ident $(if $guard:ident)?:
+                    $($from:ident)|+ -> $to:ident $({ $($arg:ident: $arg_type:ty),* $(,)? })?
+            ),+ $(,)?
+        }
+    ) => {
+        $vis trait $state_trait: Sized {
+            fn status(&self) -> $status;
+
+            /// The state for `status`, if this type can hold it.
+            fn from_status(status: $status) -> Option<Self>;
+        }
+
+        $(
+            state_machine!(@state $status_vis $state { $($($state_field: $state_field_type),*)? });
+
+            impl $state_trait for $state {
+                fn status(&self) -> $status {
+                    $status::$state { $($($state_field: self.$state_field.clone()),*)? }
+                }
+
+                fn from_status(status: $status) -> Option<Self> {
+                    match status {
+                        $status::$state { $($($state_field),*)? } => {
+                            Some($state { $($($state_field),*)? })
+                        }
+                        _ => None,
+                    }
+                }
+            }
+        )+
+
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        $status_vis enum $status {
+            $($state $({ $($state_field: $state_field_type),* })?),+
+        }
+
+        impl $status {
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $($status::$state { .. } => stringify!($state)),+
+                }
+            }
+        }
+
+        impl $state_trait for $status {
+            fn status(&self) -> $status {
+                self.clone()
+            }
+
+            fn from_status(status: $status) -> Option<Self> {
+                Some(status)
+            }
+        }
+
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        $vis struct $error {
+            pub from: $status,
+            pub transition: &'static str,
+            /// The guard that failed, if the transition is allowed from this
+            /// status at all.
+            pub guard: Option<&'static str>,
+        }
+
+        impl ::std::fmt::Display for $error {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                write!(
+                    f,
+                    "Can't {} a {} that is {}",
+                    self.transition,
+                    stringify!($machine),
+                    self.from.name()
+                )?;
+                if let Some(guard) = self.guard {
+                    write!(f, " until it {}", guard.replace('_', " "))?;
+                }
+                Ok(())
+            }
+        }
+
+        impl ::std::error::Error for $error {}
+
+        #[derive(Debug)]
+        $vis struct $machine<$s: $state_trait> {
+            state: $s,
+            $($field_vis $field: $field_type),*
+        }
+
+        impl<$s: $state_trait> $machine<$s> {
+            pub fn state(&self) -> &$s {
+                &self.state
+            }
+
+            pub fn status(&self) -> $status {
+                self.state.status()
+            }
+
+            /// The same PR with its state checked at runtime.
+            pub fn into_status(self) -> $machine<$status> {
+                let status = self.status();
+                self.with_state(status)
+            }
+
+            /// The same PR with its state held by another type, or itself
+            /// back if that type can't hold its status.
+            #[allow(clippy::result_large_err)]
+            pub fn into_state<T: $state_trait>(self) -> Result<$machine<T>, Self> {
+                match T::from_status(self.status()) {
+                    Some(state) => Ok(self.with_state(state)),
+                    None => Err(self),
+                }
+            }
+
+            fn with_state<T: $state_trait>(self, state: T) -> $machine<T> {
+                $machine {
+                    state,
+                    $($field: self.$field),*
+                }
+            }
+        }
+
+        $(
+            state_machine!(
+                @typestate $machine,
+                $transition,
+                [$($guard)?],
+                [$($from),+],
+                $to,
+                { $($($arg: $arg_type),*)? }
+            );
+        )+
+
+        impl $machine<$status> {
+            $(
+                pub fn $transition(
+                    &mut self,
+                    $($($arg: $arg_type),*)?
+                ) -> Result<&mut Self, $error> {
+                    let error = |guard| $error {
+                        from: self.state.clone(),
+                        transition: stringify!($transition),
+                        guard,
+                    };
+                    if !matches!(self.state, $($status::$from { .. })|+) {
+                        return Err(error(None));
+                    }
+                    $(
+                        if !self.$guard() {
+                            return Err(error(Some(stringify!($guard))));
+                        }
+                    )?
+                    self.state = $status::$to { $($($arg),*)? };
+                    Ok(self)
+                }
+            )+
+        }
+    };
+}